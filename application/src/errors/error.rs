@@ -1,4 +1,5 @@
 use common::types::BoxError;
+use domain::model::search_engine::QuerySyntaxError;
 use std::{error::Error, fmt};
 
 #[derive(Debug)]
@@ -10,7 +11,9 @@ pub enum AppError {
     BadRequest(String),
     DataNotFound(String),
     DataConflict(String),
+    VersionMismatch(String), // 現在のバージョン
     Inconsistent(String),
+    Aborted(String, Box<AppError>), // 失敗した段階と原因 (変更は取り消し済み)
     Unexpected(BoxError),
 }
 
@@ -24,7 +27,11 @@ impl fmt::Display for AppError {
             AppError::BadRequest(reason) => write!(f, "Bad request: {}", reason),
             AppError::DataNotFound(reason) => write!(f, "Data not found: {}", reason),
             AppError::DataConflict(reason) => write!(f, "Data conflict: {}", reason),
+            AppError::VersionMismatch(version) => {
+                write!(f, "Version mismatch: current version is \"{}\"", version)
+            }
             AppError::Inconsistent(reason) => write!(f, "Inconsistent: {}", reason),
+            AppError::Aborted(stage, e) => write!(f, "Aborted at stage '{}': {}", stage, e),
             AppError::Unexpected(e) => {
                 write!(f, "An unexpected infrastructure error occurred: {}", e)
             }
@@ -35,6 +42,7 @@ impl fmt::Display for AppError {
 impl Error for AppError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
+            AppError::Aborted(_, e) => Some(e.as_ref()),
             AppError::Unexpected(e) => Some(e.as_ref()),
             _ => None,
         }
@@ -43,6 +51,10 @@ impl Error for AppError {
 
 impl From<BoxError> for AppError {
     fn from(e: BoxError) -> Self {
-        AppError::Unexpected(e)
+        // 検索語の構文エラーは入力の誤りとして扱う
+        match e.downcast::<QuerySyntaxError>() {
+            Ok(e) => AppError::BadRequest(e.to_string()),
+            Err(e) => AppError::Unexpected(e),
+        }
     }
 }
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{
+    content::{ContentDto, FrontMatterDto},
+    search_engine::SearchRequestDto,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BulkOperationDto {
+    Publish,   // draft: false
+    Unpublish, // draft: true
+    AddTags { tags: Vec<String> },
+    RemoveTags { tags: Vec<String> },
+    SetTags { tags: Vec<String> },
+    AddCategories { categories: Vec<String> },
+    RemoveCategories { categories: Vec<String> },
+    SetCategories { categories: Vec<String> },
+    RenameTags { from: Vec<String>, to: String }, // from のタグをすべて to にする
+    RenameCategories { from: Vec<String>, to: String },
+    Delete,
+}
+
+impl BulkOperationDto {
+    // 記事に操作を適用した内容 (Delete の場合は None)
+    pub fn apply(&self, dto: ContentDto) -> Option<ContentDto> {
+        let mut dto = dto;
+        let matter = dto.matter.get_or_insert_with(FrontMatterDto::new);
+        match self {
+            Self::Publish => matter.draft = Some(false),
+            Self::Unpublish => matter.draft = Some(true),
+            Self::AddTags { tags } => add_items(&mut matter.tags, tags),
+            Self::RemoveTags { tags } => remove_items(&mut matter.tags, tags),
+            Self::SetTags { tags } => matter.tags = Some(tags.clone()),
+            Self::AddCategories { categories } => add_items(&mut matter.categories, categories),
+            Self::RemoveCategories { categories } => {
+                remove_items(&mut matter.categories, categories)
+            }
+            Self::SetCategories { categories } => matter.categories = Some(categories.clone()),
+            Self::RenameTags { from, to } => rename_items(&mut matter.tags, from, to),
+            Self::RenameCategories { from, to } => rename_items(&mut matter.categories, from, to),
+            Self::Delete => return None,
+        }
+        Some(dto)
+    }
+}
+
+fn add_items(list: &mut Option<Vec<String>>, items: &[String]) {
+    list.get_or_insert_with(Vec::new)
+        .extend(items.iter().cloned());
+}
+
+fn remove_items(list: &mut Option<Vec<String>>, items: &[String]) {
+    if let Some(list) = list {
+        list.retain(|item| !items.contains(item));
+    }
+}
+
+// 重複は ContentEntity への変換時に取り除かれる (先に出現したものを残す)
+fn rename_items(list: &mut Option<Vec<String>>, from: &[String], to: &str) {
+    if let Some(list) = list {
+        for item in list.iter_mut() {
+            if from.contains(item) {
+                *item = to.to_string();
+            }
+        }
+    }
+}
+
+// 対象は ids か selector (検索条件に一致する記事すべて) のどちらかで指定する
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkRequestDto {
+    pub ids: Option<Vec<String>>,
+    pub selector: Option<SearchRequestDto>,
+    pub operation: BulkOperationDto,
+    #[serde(default)]
+    pub dry_run: bool, // true の場合は変更内容を返すだけで適用しない
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BulkStatus {
+    Changed,
+    Unchanged,
+    NotFound,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkResultDto {
+    pub id: String,
+    pub status: BulkStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkReportDto {
+    pub dry_run: bool,
+    pub changed: usize,
+    pub unchanged: usize,
+    pub not_found: usize,
+    pub results: Vec<BulkResultDto>,
+}
+
+// タグ・カテゴリーの名前の変更と統合 (from のすべてを to にする)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeTermsDto {
+    pub from: Vec<String>,
+    pub to: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
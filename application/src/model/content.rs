@@ -1,8 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 use config::CONFIG;
+pub use domain::model::content::slugify;
 use domain::model::content::{ContentEntity, FrontMatterEntity};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -13,6 +15,13 @@ pub struct FrontMatterDto {
     pub draft: Option<bool>,
     pub tags: Option<Vec<String>>,
     pub categories: Option<Vec<String>>,
+    pub slug: Option<String>, // 未指定の場合は作成時にタイトルから生成、編集時は変更しない
+}
+
+impl Default for FrontMatterDto {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FrontMatterDto {
@@ -24,6 +33,7 @@ impl FrontMatterDto {
             draft: Some(true),
             tags: Some(Vec::new()),
             categories: Some(Vec::new()),
+            slug: None,
         }
     }
 
@@ -35,6 +45,7 @@ impl FrontMatterDto {
             draft: self.draft.or(Some(true)),
             tags: self.tags.clone().or_else(|| Some(Vec::new())),
             categories: self.categories.clone().or_else(|| Some(Vec::new())),
+            slug: self.slug.clone(),
         }
     }
 }
@@ -48,6 +59,7 @@ impl From<FrontMatterDto> for FrontMatterEntity {
             draft: dto.draft.unwrap_or(true),
             tags: dto.tags.unwrap_or_default(),
             categories: dto.categories.unwrap_or_default(),
+            slug: dto.slug.as_deref().and_then(slugify),
         }
     }
 }
@@ -61,6 +73,70 @@ impl From<FrontMatterEntity> for FrontMatterDto {
             draft: Some(entity.draft),
             tags: Some(entity.tags),
             categories: Some(entity.categories),
+            slug: entity.slug,
+        }
+    }
+}
+
+// 全角スペースを半角にし、max_len 文字までに切り詰める (oneline の場合は空白をまとめて1行にする)
+pub(crate) fn normalize_text(text: Option<String>, max_len: usize, oneline: bool) -> String {
+    let mut result = match text {
+        Some(t) => t,
+        None => return String::new(),
+    };
+    result = result.replace('\u{3000}', " ");
+    if oneline {
+        result = result
+            .split_ascii_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+    } else {
+        result = result.trim().to_string();
+    }
+    result = result.chars().take(max_len).collect();
+
+    result
+}
+
+// 記事のバージョン (保存されている内容の SHA-256)
+pub fn content_version(entity: &ContentEntity) -> String {
+    let data = serde_json::to_vec(entity).unwrap_or_default();
+    Sha256::digest(&data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// If-Match で指定された更新の前提条件
+#[derive(Debug, Clone, PartialEq)]
+pub enum IfMatch {
+    Any,                   // * (バージョンを確認しない)
+    Versions(Vec<String>), // いずれかのバージョンと一致すること
+}
+
+impl IfMatch {
+    // "v1", W/"v2" や * の形式 (引用符の無い値もそのまま受け付ける)
+    pub fn parse(value: &str) -> Self {
+        if value.trim() == "*" {
+            return Self::Any;
+        }
+        let versions = value
+            .split(',')
+            .map(|v| {
+                v.trim()
+                    .trim_start_matches("W/")
+                    .trim_matches('"')
+                    .to_string()
+            })
+            .filter(|v| !v.is_empty())
+            .collect();
+        Self::Versions(versions)
+    }
+
+    pub fn matches(&self, version: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Versions(versions) => versions.iter().any(|v| v == version),
         }
     }
 }
@@ -70,6 +146,14 @@ pub struct ContentDto {
     pub id: Option<String>,
     pub matter: Option<FrontMatterDto>,
     pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>, // 編集時は取得した時点のバージョンを指定 (If-Match でも可)
+}
+
+impl Default for ContentDto {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ContentDto {
@@ -78,6 +162,7 @@ impl ContentDto {
             id: Some(Uuid::new_v4().to_string()),
             matter: Some(FrontMatterDto::new()),
             body: Some(String::new()),
+            version: None,
         }
     }
 
@@ -90,13 +175,14 @@ impl ContentDto {
                     .map_or_else(FrontMatterDto::new, |fm| fm.default()),
             ),
             body: self.body.clone().or_else(|| Some(String::new())),
+            version: self.version.clone(),
         }
     }
 }
 
 impl From<ContentDto> for ContentEntity {
     fn from(dto: ContentDto) -> Self {
-        let fm = dto.matter.unwrap_or_else(FrontMatterDto::new);
+        let fm = dto.matter.unwrap_or_default();
 
         fn normalize_list(list: Option<Vec<String>>, limit: usize, max_len: usize) -> Vec<String> {
             use std::collections::HashSet;
@@ -126,25 +212,6 @@ impl From<ContentDto> for ContentEntity {
             result
         }
 
-        fn normalize_text(text: Option<String>, max_len: usize, oneline: bool) -> String {
-            let mut result = match text {
-                Some(t) => t,
-                None => return String::new(),
-            };
-            result = result.replace('\u{3000}', " ");
-            if oneline {
-                result = result
-                    .split_ascii_whitespace()
-                    .collect::<Vec<_>>()
-                    .join(" ");
-            } else {
-                result = result.trim().to_string();
-            }
-            result = result.chars().take(max_len).collect();
-
-            result
-        }
-
         Self {
             id: dto.id.unwrap_or(Uuid::new_v4().to_string()),
             matter: FrontMatterEntity {
@@ -168,6 +235,7 @@ impl From<ContentDto> for ContentEntity {
                     CONFIG.content.max_categories,
                     CONFIG.content.category_max_len,
                 ),
+                slug: fm.slug.as_deref().and_then(slugify),
             },
             body: normalize_text(dto.body, CONFIG.content.body_max_len, false),
         }
@@ -177,9 +245,72 @@ impl From<ContentDto> for ContentEntity {
 impl From<ContentEntity> for ContentDto {
     fn from(entity: ContentEntity) -> Self {
         Self {
+            version: Some(content_version(&entity)),
             id: Some(entity.id),
             matter: Some(FrontMatterDto::from(entity.matter)),
             body: Some(entity.body),
         }
     }
 }
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RebuildReportDto {
+    pub processed: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+}
+
+// Markdown・HTML・検索インデックスの食い違い (HTML は html_dir からの相対パス)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckReportDto {
+    pub orphan_html: Vec<String>,      // 対応する記事の無い HTML
+    pub orphan_markdown: Vec<String>, // 検索インデックスに無い、または同じ id の古い複製の Markdown
+    pub missing_markdown: Vec<String>, // Markdown の無い検索インデックスの記事 id
+    pub draft_html: Vec<String>,      // 下書き・予約投稿なのに出力されている HTML
+    pub repaired: bool,
+    pub changes: Vec<String>, // repair で行った変更
+    pub errors: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_match_any() {
+        assert_eq!(IfMatch::parse("*"), IfMatch::Any);
+        assert_eq!(IfMatch::parse(" * "), IfMatch::Any);
+        assert!(IfMatch::Any.matches("abc"));
+    }
+
+    #[test]
+    fn if_match_quoted_and_weak() {
+        assert_eq!(
+            IfMatch::parse("\"abc\""),
+            IfMatch::Versions(vec!["abc".into()])
+        );
+        assert_eq!(
+            IfMatch::parse("W/\"abc\""),
+            IfMatch::Versions(vec!["abc".into()])
+        );
+        assert_eq!(IfMatch::parse("abc"), IfMatch::Versions(vec!["abc".into()]));
+    }
+
+    #[test]
+    fn if_match_list() {
+        let if_match = IfMatch::parse("\"v1\", W/\"v2\" ,, \"\"");
+        assert_eq!(if_match, IfMatch::Versions(vec!["v1".into(), "v2".into()]));
+        assert!(if_match.matches("v1"));
+        assert!(if_match.matches("v2"));
+        assert!(!if_match.matches("v3"));
+    }
+
+    #[test]
+    fn if_match_empty_matches_nothing() {
+        let if_match = IfMatch::parse("");
+        assert_eq!(if_match, IfMatch::Versions(vec![]));
+        assert!(!if_match.matches(""));
+    }
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use domain::model::media::MediaEntity;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaUploadDto {
+    pub file_name: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaDto {
+    pub name: String,
+    pub url: String,
+    pub content_type: String,
+    pub size: u64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<MediaEntity> for MediaDto {
+    fn from(entity: MediaEntity) -> Self {
+        Self {
+            name: entity.name,
+            url: entity.url,
+            content_type: entity.content_type,
+            size: entity.size,
+            updated_at: entity.updated_at,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::model::content::{normalize_text, slugify};
+use config::CONFIG;
+use domain::model::page::{PageEntity, PageMatterEntity};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageMatterDto {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub draft: Option<bool>,
+    pub template: Option<String>, // template_dir 内のファイル名 (未指定の場合は template_page)
+    pub order: Option<i32>,       // メニューの並び順 (昇順)
+    pub date: Option<DateTime<Utc>>, // 作成日時 (作成時に設定され、編集しても変わらないため指定は不要)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageDto {
+    pub id: Option<String>, // URL (/{id}.html) に使う。作成時に未指定の場合はタイトルから生成
+    pub matter: Option<PageMatterDto>,
+    pub body: Option<String>,
+}
+
+impl PageDto {
+    // id は slugify した値 (URL に使えない場合は None)
+    pub fn to_entity(&self) -> Option<PageEntity> {
+        let matter = self.matter.clone().unwrap_or(PageMatterDto {
+            title: None,
+            description: None,
+            draft: None,
+            template: None,
+            order: None,
+            date: None,
+        });
+        let title = normalize_text(matter.title, CONFIG.content.title_max_len, true);
+        let id = match &self.id {
+            Some(id) => slugify(id)?,
+            None => slugify(&title)?,
+        };
+        let description =
+            normalize_text(matter.description, CONFIG.content.description_max_len, true);
+
+        Some(PageEntity {
+            id,
+            matter: PageMatterEntity {
+                date: Utc::now(),
+                title,
+                description: (!description.is_empty()).then_some(description),
+                draft: matter.draft.unwrap_or(true),
+                template: matter
+                    .template
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty()),
+                order: matter.order.unwrap_or_default(),
+            },
+            body: normalize_text(self.body.clone(), CONFIG.content.body_max_len, false),
+        })
+    }
+}
+
+impl From<PageEntity> for PageDto {
+    fn from(entity: PageEntity) -> Self {
+        Self {
+            id: Some(entity.id),
+            matter: Some(PageMatterDto {
+                title: Some(entity.matter.title),
+                description: entity.matter.description,
+                draft: Some(entity.matter.draft),
+                template: entity.matter.template,
+                order: Some(entity.matter.order),
+                date: Some(entity.matter.date),
+            }),
+            body: Some(entity.body),
+        }
+    }
+}
+
+// メニュー・一覧用 (本文なし)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageSummaryDto {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub draft: bool,
+    pub order: i32,
+    pub date: DateTime<Utc>,
+}
+
+impl From<PageEntity> for PageSummaryDto {
+    fn from(entity: PageEntity) -> Self {
+        Self {
+            id: entity.id,
+            title: entity.matter.title,
+            description: entity.matter.description,
+            draft: entity.matter.draft,
+            order: entity.matter.order,
+            date: entity.matter.date,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::model::content::ContentDto;
+use domain::model::revision::RevisionEntity;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionDto {
+    pub revision: String,
+    pub account: String,
+    pub created_at: DateTime<Utc>,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<ContentDto>, // 一覧では省略
+}
+
+impl RevisionDto {
+    pub fn summary(entity: RevisionEntity) -> Self {
+        Self {
+            content: None,
+            ..Self::from(entity)
+        }
+    }
+}
+
+impl From<RevisionEntity> for RevisionDto {
+    fn from(entity: RevisionEntity) -> Self {
+        Self {
+            revision: entity.revision,
+            account: entity.account,
+            created_at: entity.created_at,
+            title: entity.content.matter.title.clone(),
+            content: Some(ContentDto::from(entity.content)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevisionDiffQueryDto {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLineDto {
+    pub op: DiffOp,
+    pub old_line: Option<usize>, // 1 始まり (insert の場合は None)
+    pub new_line: Option<usize>, // 1 始まり (delete の場合は None)
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionDiffDto {
+    pub id: String,
+    pub from: RevisionDto,
+    pub to: RevisionDto,
+    pub inserted: usize,
+    pub deleted: usize,
+    pub lines: Vec<DiffLineDto>,
+}
@@ -4,7 +4,10 @@ use serde::{Deserialize, Serialize};
 use config::CONFIG;
 use domain::model::{
     content::ContentEntity,
-    search_engine::{SearchParams, SearchResult},
+    search_engine::{
+        MonthCount, SearchFacets, SearchHighlight, SearchParams, SearchResult, SearchSort,
+        SuggestField,
+    },
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -16,6 +19,7 @@ pub struct SearchFrontMatterDto {
     pub draft: bool,
     pub tags: Vec<String>,
     pub categories: Vec<String>,
+    pub slug: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -32,7 +36,32 @@ pub struct SearchResponseDto {
     pub per_page: usize,
     pub max_page: usize,
     pub overflow: bool,
+    #[serde(default)]
+    pub total: usize, // 検索条件に一致した件数 (search_limit で切り詰める前)
     pub contents: Vec<SearchContentDto>,
+    #[serde(default)]
+    pub highlights: Vec<SearchHighlightDto>, // word を指定した場合のみ
+    #[serde(default)]
+    pub facets: SearchFacetsDto,
+}
+
+// 検索条件に一致したすべての記事の項目ごとの件数
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFacetsDto {
+    pub tags: Vec<(String, u64)>,       // 件数の多い順
+    pub categories: Vec<(String, u64)>, // 件数の多い順
+    pub draft: Vec<(bool, u64)>,
+    pub months: Vec<ArchiveCountDto>, // 新しい年月順
+}
+
+// 検索語を強調した抜粋 (HTML エスケープ済み)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHighlightDto {
+    pub id: String,
+    pub title: Option<String>,
+    pub body: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -46,12 +75,73 @@ pub struct SearchRequestDto {
     pub categories: Option<Vec<String>>, // 各値に完全一致
     pub page: Option<usize>,  // ページ
     pub per_page: Option<usize>, // ページ内行数
+    pub sort: Option<SearchSortDto>, // 並び順 (未指定の場合は日付の新しい順)
+    pub advanced: Option<bool>, // true の場合は word を高度な検索構文として扱う
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSortDto {
+    Relevance, // 検索語との関連度の高い順 (word が無い場合は日付の新しい順)
+    DateDesc,
+    DateAsc,
+    Title, // タイトルの昇順
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveCountDto {
+    pub year: i32,
+    pub month: u32,
+    pub count: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchQueryDto {
-    pub page: Option<usize>,     // ページ
-    pub per_page: Option<usize>, // ページ内行数
+    pub page: Option<usize>,         // ページ
+    pub per_page: Option<usize>,     // ページ内行数
+    pub sort: Option<SearchSortDto>, // 並び順
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestFieldDto {
+    Tags,
+    Categories,
+    Title,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuggestQueryDto {
+    pub field: SuggestFieldDto,
+    pub prefix: String,       // 前方一致 (大文字・小文字を区別する)
+    pub limit: Option<usize>, // 件数 (1〜50)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedQueryDto {
+    pub limit: Option<usize>, // 件数 (1〜20)
+}
+
+impl From<SearchSortDto> for SearchSort {
+    fn from(dto: SearchSortDto) -> Self {
+        match dto {
+            SearchSortDto::Relevance => Self::Relevance,
+            SearchSortDto::DateDesc => Self::DateDesc,
+            SearchSortDto::DateAsc => Self::DateAsc,
+            SearchSortDto::Title => Self::Title,
+        }
+    }
+}
+
+impl From<SuggestFieldDto> for SuggestField {
+    fn from(dto: SuggestFieldDto) -> Self {
+        match dto {
+            SuggestFieldDto::Tags => Self::Tags,
+            SuggestFieldDto::Categories => Self::Categories,
+            SuggestFieldDto::Title => Self::Title,
+        }
+    }
 }
 
 impl From<SearchRequestDto> for SearchParams {
@@ -65,6 +155,9 @@ impl From<SearchRequestDto> for SearchParams {
             categories: dto.categories,
             page: dto.page,
             per_page: dto.per_page,
+            sort: dto.sort.map(SearchSort::from),
+            advanced: dto.advanced.unwrap_or(false),
+            facets: true,
         }
     }
 }
@@ -80,6 +173,9 @@ impl From<SearchQueryDto> for SearchParams {
             categories: None,
             page: dto.page,
             per_page: dto.per_page,
+            sort: dto.sort.map(SearchSort::from),
+            advanced: false,
+            facets: true,
         }
     }
 }
@@ -114,6 +210,7 @@ impl From<ContentEntity> for SearchContentDto {
                 draft: entity.matter.draft,
                 tags: entity.matter.tags,
                 categories: entity.matter.categories,
+                slug: entity.matter.slug,
             },
         }
     }
@@ -126,7 +223,49 @@ impl From<SearchResult> for SearchResponseDto {
             per_page: result.per_page,
             max_page: result.max_page,
             overflow: result.overflow,
+            total: result.total,
             contents: result.contents.into_iter().map(|c| c.into()).collect(),
+            highlights: result
+                .highlights
+                .into_iter()
+                .map(SearchHighlightDto::from)
+                .collect(),
+            facets: SearchFacetsDto::from(result.facets),
+        }
+    }
+}
+
+impl From<SearchFacets> for SearchFacetsDto {
+    fn from(facets: SearchFacets) -> Self {
+        Self {
+            tags: facets.tags,
+            categories: facets.categories,
+            draft: facets.draft,
+            months: facets
+                .months
+                .into_iter()
+                .map(ArchiveCountDto::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<SearchHighlight> for SearchHighlightDto {
+    fn from(highlight: SearchHighlight) -> Self {
+        Self {
+            id: highlight.id,
+            title: highlight.title,
+            body: highlight.body,
+        }
+    }
+}
+
+impl From<MonthCount> for ArchiveCountDto {
+    fn from(entity: MonthCount) -> Self {
+        Self {
+            year: entity.year,
+            month: entity.month,
+            count: entity.count,
         }
     }
 }
@@ -1,23 +1,39 @@
-use crate::usecase::{auth::AuthUseCase, content::ContentUseCase};
+use crate::usecase::{
+    auth::AuthUseCase, content::ContentUseCase, media::MediaUseCase, page::PageUseCase,
+};
 use domain::Repositories;
 use std::sync::Arc;
+use tokio::sync::Mutex;
 
 pub trait UseCaseModule: Send + Sync {
     fn content(&self) -> &ContentUseCase;
     fn auth(&self) -> &AuthUseCase;
+    fn media(&self) -> &MediaUseCase;
+    fn page(&self) -> &PageUseCase;
 }
 
 pub struct UseCaseModuleImpl {
     content: ContentUseCase,
     auth: AuthUseCase,
+    media: MediaUseCase,
+    page: PageUseCase,
 }
 
 impl UseCaseModuleImpl {
     pub fn new(repositories: Arc<dyn Repositories>) -> Self {
-        let content = ContentUseCase::new(repositories.clone());
-        let auth = AuthUseCase::new(repositories);
+        // 記事と固定ページは同じ作業単位を使うため、変更の排他も共有する
+        let lock = Arc::new(Mutex::new(()));
+        let content = ContentUseCase::new(repositories.clone(), lock.clone());
+        let auth = AuthUseCase::new(repositories.clone());
+        let page = PageUseCase::new(repositories.clone(), lock);
+        let media = MediaUseCase::new(repositories);
 
-        Self { content, auth }
+        Self {
+            content,
+            auth,
+            media,
+            page,
+        }
     }
 }
 
@@ -30,4 +46,12 @@ impl UseCaseModule for UseCaseModuleImpl {
     fn auth(&self) -> &AuthUseCase {
         &self.auth
     }
+
+    fn media(&self) -> &MediaUseCase {
+        &self.media
+    }
+
+    fn page(&self) -> &PageUseCase {
+        &self.page
+    }
 }
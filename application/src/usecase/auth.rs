@@ -162,7 +162,7 @@ impl AuthUseCase {
 
     pub async fn authenticate(&self, token: &str) -> Result<MemberSigninResponseDto, AppError> {
         let claims = simple_jwt::decode(
-            &token,
+            token,
             &CONFIG.security.issuer,
             &CONFIG.security.secret,
             true,
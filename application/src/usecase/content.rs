@@ -1,47 +1,122 @@
 use crate::{
     errors::error::AppError,
     model::{
-        content::ContentDto,
-        search_engine::{SearchQueryDto, SearchRequestDto, SearchResponseDto},
+        bulk::{
+            BulkOperationDto, BulkReportDto, BulkRequestDto, BulkResultDto, BulkStatus,
+            MergeTermsDto,
+        },
+        content::{
+            CheckReportDto, ContentDto, IfMatch, RebuildReportDto, content_version, slugify,
+        },
+        revision::{DiffLineDto, DiffOp, RevisionDiffDto, RevisionDto},
+        search_engine::{
+            ArchiveCountDto, SearchContentDto, SearchQueryDto, SearchRequestDto, SearchResponseDto,
+            SuggestQueryDto,
+        },
+    },
+    usecase::stage::{
+        STAGE_COMMIT, STAGE_HTML, STAGE_INDEX, STAGE_MARKDOWN, STAGE_PAGES, STAGE_REVISION, Stage,
+        StageError, rollback,
     },
 };
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use config::CONFIG;
 use domain::{
     Repositories,
-    model::{content::ContentEntity, search_engine::SearchParams},
+    model::{
+        archive::ArchiveKind,
+        content::{ContentEntity, assign_unique_slug},
+        revision::RevisionEntity,
+        search_engine::{SearchIndexEntry, SearchParams},
+    },
+};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::Path,
+    sync::Arc,
 };
-use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// 検索インデックスのコミット後に失敗した場合に戻す内容
+// (id と変更前のドキュメント、新規の場合は None)
+type IndexUndo = Vec<(String, Option<ContentEntity>)>;
 
 pub struct ContentUseCase {
     repositories: Arc<dyn Repositories>,
+    lock: Arc<Mutex<()>>, // 記事・固定ページの変更は同時に1つだけ実行する
+    // 静的サイト用の検索インデックスの語句 (id → 記事の版と語句、変わった記事だけ分かち書きし直す)
+    search_terms: Mutex<HashMap<String, (String, Vec<String>)>>,
 }
 
 impl ContentUseCase {
-    pub fn new(repositories: Arc<dyn Repositories>) -> Self {
-        Self { repositories }
+    pub fn new(repositories: Arc<dyn Repositories>, lock: Arc<Mutex<()>>) -> Self {
+        Self {
+            repositories,
+            lock,
+            search_terms: Mutex::new(HashMap::new()),
+        }
     }
 
-    pub async fn create(&self, dto: &ContentDto) -> Result<ContentDto, AppError> {
+    pub async fn create(&self, dto: &ContentDto, account: &str) -> Result<ContentDto, AppError> {
         tracing::debug!("create dto: {:?}", dto);
 
-        let entity = ContentEntity::from(dto.clone());
+        let mut entity = ContentEntity::from(dto.clone());
         if entity.matter.title.is_empty() || entity.body.is_empty() {
             return Err(AppError::BadRequest("title or body is empty".into()));
         }
 
         tracing::debug!("create entity: {:?}", entity);
 
+        let _guard = self.lock.lock().await;
+
         let count = self.repositories.engine().count(&entity.id).await?;
         if count > 0 {
-            return Err(AppError::DataConflict(entity.id).into());
+            return Err(AppError::DataConflict(entity.id));
         }
 
-        let saved_entity = self.repositories.content().create(&entity).await?;
+        entity.matter.slug = self.resolve_slug(&entity, has_slug(dto)).await?;
+        self.check_page_conflict(&entity).await?;
+
+        self.repositories.unit_of_work().begin().await?;
+        match self.create_staged(&entity, account, true).await {
+            Ok(saved_entity) => {
+                self.repositories.unit_of_work().commit().await?;
+                Ok(ContentDto::from(saved_entity))
+            }
+            Err(e) => Err(self.abort(e, vec![(entity.id.clone(), None)]).await),
+        }
+    }
+
+    // write_markdown が false の場合は markdown_dir のファイルをそのまま使う (ファイルの監視から反映する場合)
+    async fn create_staged(
+        &self,
+        entity: &ContentEntity,
+        account: &str,
+        write_markdown: bool,
+    ) -> Result<ContentEntity, StageError> {
+        let saved_entity = if write_markdown {
+            self.repositories
+                .content()
+                .create(entity)
+                .await
+                .stage(STAGE_MARKDOWN)?
+        } else {
+            entity.clone()
+        };
+        self.save_revision(&saved_entity, account)
+            .await
+            .stage(STAGE_REVISION)?;
 
+        let related = self
+            .related_entities(&saved_entity.id)
+            .await
+            .stage(STAGE_HTML)?;
         let html_text = self
             .repositories
             .parser()
-            .create(&saved_entity.clone())
-            .await?;
+            .create(&saved_entity, &related)
+            .await
+            .stage(STAGE_HTML)?;
 
         let index_entity = ContentEntity {
             id: saved_entity.id.clone(),
@@ -49,10 +124,23 @@ impl ContentUseCase {
             body: html_text,
         };
 
-        self.repositories.engine().create(&index_entity).await?;
-        self.repositories.engine().commit().await?;
+        self.repositories
+            .engine()
+            .create(&index_entity)
+            .await
+            .stage(STAGE_INDEX)?;
+        self.repositories
+            .unit_of_work()
+            .commit_index()
+            .await
+            .stage(STAGE_COMMIT)?;
+
+        self.render_related(std::slice::from_ref(&saved_entity.id))
+            .await
+            .stage(STAGE_PAGES)?;
+        self.render_pages().await.stage(STAGE_PAGES)?;
 
-        Ok(ContentDto::from(saved_entity))
+        Ok(saved_entity)
     }
 
     pub async fn search(&self, dto: &SearchRequestDto) -> Result<SearchResponseDto, AppError> {
@@ -81,52 +169,256 @@ impl ContentUseCase {
         }
     }
 
-    pub async fn remove(&self, id: &str) -> Result<serde_json::Value, AppError> {
+    pub async fn find_by_slug(&self, slug: &str) -> Result<Option<ContentDto>, AppError> {
+        match self.repositories.engine().find_by_slug(slug).await? {
+            Some(plain) => {
+                let content = self
+                    .repositories
+                    .content()
+                    .find(&plain.id, &plain.matter.date)
+                    .await?;
+                Ok(content.map(ContentDto::from))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // if_match が None の場合はバージョンを確認しない
+    pub async fn remove(
+        &self,
+        id: &str,
+        if_match: Option<&IfMatch>,
+    ) -> Result<serde_json::Value, AppError> {
         tracing::debug!("remove id: {}", id);
 
-        let a = self.repositories.parser().remove(id).await?;
-        let b = self.repositories.content().remove(id).await?;
-        let c = self.repositories.engine().remove(id).await?;
-        self.repositories.engine().commit().await?;
+        let _guard = self.lock.lock().await;
+
+        let old = self.repositories.engine().find(id).await?;
+        if let Some(if_match) = if_match {
+            let plain = old
+                .as_ref()
+                .ok_or_else(|| AppError::DataNotFound(id.to_string()))?;
+            self.check_version(plain, if_match).await?;
+        }
+
+        self.repositories.unit_of_work().begin().await?;
+        match self.remove_staged(id, old.as_ref(), true).await {
+            Ok((a, b, c)) => {
+                self.repositories.unit_of_work().commit().await?;
+                tracing::debug!("remove html: {}, content: {}, index: {}", a, b, c);
+
+                Ok(serde_json::json!({
+                    "id": id,
+                    "html": a,
+                    "markdown": b,
+                    "index": c
+                }))
+            }
+            Err(e) => Err(self.abort(e, vec![(id.to_string(), old)]).await),
+        }
+    }
+
+    async fn remove_staged(
+        &self,
+        id: &str,
+        old: Option<&ContentEntity>,
+        write_markdown: bool,
+    ) -> Result<(u64, u64, usize), StageError> {
+        let removed = self.delete_staged(id, old, write_markdown).await?;
+        self.repositories
+            .unit_of_work()
+            .commit_index()
+            .await
+            .stage(STAGE_COMMIT)?;
+
+        self.render_related(&[id.to_string()])
+            .await
+            .stage(STAGE_PAGES)?;
+        self.render_pages().await.stage(STAGE_PAGES)?;
 
-        tracing::debug!("remove html: {}, content: {}, index: {}", a, b, c);
+        Ok(removed)
+    }
+
+    // 記事の HTML・Markdown・インデックスを削除する (コミットはしない)
+    // write_markdown が false の場合は Markdown を削除しない
+    async fn delete_staged(
+        &self,
+        id: &str,
+        old: Option<&ContentEntity>,
+        write_markdown: bool,
+    ) -> Result<(u64, u64, usize), StageError> {
+        let mut a = match old {
+            Some(plain) => self
+                .repositories
+                .parser()
+                .remove(plain)
+                .await
+                .stage(STAGE_HTML)?,
+            None => 0,
+        };
+        // 旧 URL に残したリダイレクト用 HTML も削除する
+        a += self
+            .repositories
+            .parser()
+            .remove_redirects(id)
+            .await
+            .stage(STAGE_HTML)?;
+        let b = if write_markdown {
+            self.repositories
+                .content()
+                .remove(id)
+                .await
+                .stage(STAGE_MARKDOWN)?
+        } else {
+            0
+        };
+        let c = self
+            .repositories
+            .engine()
+            .remove(id)
+            .await
+            .stage(STAGE_INDEX)?;
 
-        Ok(serde_json::json!({
-            "id": id,
-            "html": a,
-            "markdown": b,
-            "index": c
-        }))
+        Ok((a, b, c))
     }
 
-    pub async fn edit(&self, dto: &ContentDto) -> Result<ContentDto, AppError> {
+    // if_match が None の場合は dto.version と一致することを確認する (どちらも無い場合は BadRequest)
+    pub async fn edit(
+        &self,
+        dto: &ContentDto,
+        if_match: Option<&IfMatch>,
+        account: &str,
+    ) -> Result<ContentDto, AppError> {
         let id = dto
             .id
             .clone()
             .ok_or_else(|| AppError::BadRequest("id is required".into()))?;
+        let if_match = match (if_match, &dto.version) {
+            (Some(if_match), _) => if_match.clone(),
+            (None, Some(version)) => IfMatch::Versions(vec![version.clone()]),
+            (None, None) => {
+                return Err(AppError::BadRequest(
+                    "version or If-Match header is required".into(),
+                ));
+            }
+        };
 
-        let entity = ContentEntity::from(dto.clone());
+        let mut entity = ContentEntity::from(dto.clone());
         if entity.matter.title.is_empty() || entity.body.is_empty() {
             return Err(AppError::BadRequest("title or body is empty".into()));
         }
 
-        let count = self.repositories.engine().count(&id).await?;
-        if count == 0 {
-            return Err(AppError::DataNotFound(id).into());
+        let _guard = self.lock.lock().await;
+
+        let Some(old) = self.repositories.engine().find(&id).await? else {
+            return Err(AppError::DataNotFound(id));
+        };
+        self.check_version(&old, &if_match).await?;
+
+        // slug の指定が無い場合は変更しない
+        entity.matter.slug = if has_slug(dto) {
+            self.resolve_slug(&entity, true).await?
+        } else {
+            old.matter.slug.clone()
+        };
+        self.check_page_conflict(&entity).await?;
+
+        self.repositories.unit_of_work().begin().await?;
+        match self.edit_staged(&entity, &old, account, true).await {
+            Ok(saved_entity) => {
+                self.repositories.unit_of_work().commit().await?;
+                Ok(ContentDto::from(saved_entity))
+            }
+            Err(e) => Err(self.abort(e, vec![(id, Some(old))]).await),
         }
+    }
+
+    async fn edit_staged(
+        &self,
+        entity: &ContentEntity,
+        old: &ContentEntity,
+        account: &str,
+        write_markdown: bool,
+    ) -> Result<ContentEntity, StageError> {
+        let saved_entity = self
+            .replace_staged(entity, old, account, write_markdown)
+            .await?;
+        self.repositories
+            .unit_of_work()
+            .commit_index()
+            .await
+            .stage(STAGE_COMMIT)?;
+
+        self.render_related(std::slice::from_ref(&saved_entity.id))
+            .await
+            .stage(STAGE_PAGES)?;
+        self.render_pages().await.stage(STAGE_PAGES)?;
 
-        let a = self.repositories.parser().remove(&id).await?;
-        let b = self.repositories.content().remove(&id).await?;
-        let c = self.repositories.engine().remove(&id).await?;
+        Ok(saved_entity)
+    }
+
+    // 記事を old から entity の内容に置き換える (コミットはしない)
+    // write_markdown が false の場合は Markdown を書き換えない
+    async fn replace_staged(
+        &self,
+        entity: &ContentEntity,
+        old: &ContentEntity,
+        account: &str,
+        write_markdown: bool,
+    ) -> Result<ContentEntity, StageError> {
+        let published = self
+            .repositories
+            .parser()
+            .exists(old)
+            .await
+            .stage(STAGE_HTML)?;
+
+        let a = self
+            .repositories
+            .parser()
+            .remove(old)
+            .await
+            .stage(STAGE_HTML)?;
+        let b = if write_markdown {
+            self.repositories
+                .content()
+                .remove(&old.id)
+                .await
+                .stage(STAGE_MARKDOWN)?
+        } else {
+            0
+        };
+        let c = self
+            .repositories
+            .engine()
+            .remove(&old.id)
+            .await
+            .stage(STAGE_INDEX)?;
         tracing::debug!("edit html: {}, content: {}, index: {}", a, b, c);
 
-        let saved_entity = self.repositories.content().create(&entity).await?;
+        let saved_entity = if write_markdown {
+            self.repositories
+                .content()
+                .create(entity)
+                .await
+                .stage(STAGE_MARKDOWN)?
+        } else {
+            entity.clone()
+        };
+        self.save_revision(&saved_entity, account)
+            .await
+            .stage(STAGE_REVISION)?;
 
+        let related = self
+            .related_entities(&saved_entity.id)
+            .await
+            .stage(STAGE_HTML)?;
         let html_text = self
             .repositories
             .parser()
-            .create(&saved_entity.clone())
-            .await?;
+            .create(&saved_entity, &related)
+            .await
+            .stage(STAGE_HTML)?;
 
         let index_entity = ContentEntity {
             id: saved_entity.id.clone(),
@@ -134,19 +426,1602 @@ impl ContentUseCase {
             body: html_text,
         };
 
-        self.repositories.engine().edit(&index_entity).await?;
-        self.repositories.engine().commit().await?;
+        self.repositories
+            .engine()
+            .edit(&index_entity)
+            .await
+            .stage(STAGE_INDEX)?;
+
+        // 公開中の記事の URL が変わった場合は旧 URL にリダイレクトを残す
+        if published
+            && self
+                .repositories
+                .parser()
+                .exists(&saved_entity)
+                .await
+                .stage(STAGE_HTML)?
+        {
+            self.repositories
+                .parser()
+                .create_redirect(old, &saved_entity)
+                .await
+                .stage(STAGE_HTML)?;
+        }
+
+        Ok(saved_entity)
+    }
+
+    // ids または selector で指定した記事にまとめて操作を適用する
+    // 変更は1回のコミットでまとめて確定し、途中で失敗した場合はすべて取り消す
+    pub async fn bulk(
+        &self,
+        dto: &BulkRequestDto,
+        account: &str,
+    ) -> Result<BulkReportDto, AppError> {
+        let _guard = self.lock.lock().await;
+
+        let ids = match (&dto.ids, &dto.selector) {
+            (Some(ids), None) => ids.clone(),
+            (None, Some(selector)) => self
+                .repositories
+                .engine()
+                .search_all(&SearchParams::from(selector.clone()))
+                .await?
+                .into_iter()
+                .map(|content| content.id)
+                .collect(),
+            _ => {
+                return Err(AppError::BadRequest(
+                    "either ids or selector is required".into(),
+                ));
+            }
+        };
+        if ids.len() > CONFIG.search.search_limit {
+            return Err(AppError::BadRequest(format!(
+                "too many targets: {} (limit = {})",
+                ids.len(),
+                CONFIG.search.search_limit
+            )));
+        }
+
+        self.bulk_ids(ids, &dto.operation, dto.dry_run, account)
+            .await
+    }
+
+    // タグの名前を変更する (複数指定した場合は1つに統合する)
+    pub async fn merge_tags(
+        &self,
+        dto: &MergeTermsDto,
+        account: &str,
+    ) -> Result<BulkReportDto, AppError> {
+        let operation = BulkOperationDto::RenameTags {
+            from: dto.from.clone(),
+            to: dto.to.clone(),
+        };
+        self.merge_terms(dto, &operation, account, |term| SearchParams {
+            tags: Some(vec![term.to_string()]),
+            ..no_conditions()
+        })
+        .await
+    }
+
+    // カテゴリーの名前を変更する (複数指定した場合は1つに統合する)
+    pub async fn merge_categories(
+        &self,
+        dto: &MergeTermsDto,
+        account: &str,
+    ) -> Result<BulkReportDto, AppError> {
+        let operation = BulkOperationDto::RenameCategories {
+            from: dto.from.clone(),
+            to: dto.to.clone(),
+        };
+        self.merge_terms(dto, &operation, account, |term| SearchParams {
+            categories: Some(vec![term.to_string()]),
+            ..no_conditions()
+        })
+        .await
+    }
+
+    // from のいずれかを持つ記事すべてに operation を適用する
+    async fn merge_terms(
+        &self,
+        dto: &MergeTermsDto,
+        operation: &BulkOperationDto,
+        account: &str,
+        params: impl Fn(&str) -> SearchParams,
+    ) -> Result<BulkReportDto, AppError> {
+        if dto.from.is_empty() || dto.to.trim().is_empty() {
+            return Err(AppError::BadRequest("from or to is empty".into()));
+        }
+
+        let _guard = self.lock.lock().await;
+
+        let mut ids = Vec::new();
+        for term in &dto.from {
+            for content in self.repositories.engine().search_all(&params(term)).await? {
+                if !ids.contains(&content.id) {
+                    ids.push(content.id);
+                }
+            }
+        }
+
+        self.bulk_ids(ids, operation, dto.dry_run, account).await
+    }
+
+    // 記事ごとに operation を適用した結果をまとめ、dry_run でなければ1回のコミットで確定する
+    async fn bulk_ids(
+        &self,
+        ids: Vec<String>,
+        operation: &BulkOperationDto,
+        dry_run: bool,
+        account: &str,
+    ) -> Result<BulkReportDto, AppError> {
+        let mut seen = HashSet::new();
+        let mut results = Vec::new();
+        let mut targets = Vec::new(); // (変更前のドキュメント, 変更後の内容 (削除の場合は None))
+        for id in ids {
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            let current = match self.repositories.engine().find(&id).await? {
+                Some(plain) => self
+                    .repositories
+                    .content()
+                    .find(&plain.id, &plain.matter.date)
+                    .await?
+                    .map(|current| (plain, current)),
+                None => None,
+            };
+            let Some((plain, current)) = current else {
+                results.push(BulkResultDto {
+                    id,
+                    status: BulkStatus::NotFound,
+                    title: None,
+                });
+                continue;
+            };
+
+            // 入力と同じ正規化をした内容同士で比べる
+            let current = ContentEntity::from(ContentDto::from(current));
+            let updated = operation
+                .apply(ContentDto::from(current.clone()))
+                .map(ContentEntity::from);
+            let status = if updated.as_ref() == Some(&current) {
+                BulkStatus::Unchanged
+            } else {
+                BulkStatus::Changed
+            };
+            results.push(BulkResultDto {
+                id,
+                status,
+                title: Some(current.matter.title.clone()),
+            });
+            if status == BulkStatus::Changed {
+                targets.push((plain, updated));
+            }
+        }
 
-        Ok(ContentDto::from(saved_entity))
+        let count = |status| results.iter().filter(|r| r.status == status).count();
+        let (changed, unchanged, not_found) = (
+            count(BulkStatus::Changed),
+            count(BulkStatus::Unchanged),
+            count(BulkStatus::NotFound),
+        );
+        let report = BulkReportDto {
+            dry_run,
+            changed,
+            unchanged,
+            not_found,
+            results,
+        };
+        if dry_run || targets.is_empty() {
+            return Ok(report);
+        }
+
+        self.repositories.unit_of_work().begin().await?;
+        match self.bulk_staged(&targets, account).await {
+            Ok(()) => {
+                self.repositories.unit_of_work().commit().await?;
+                Ok(report)
+            }
+            Err(e) => {
+                let undo = targets
+                    .into_iter()
+                    .map(|(plain, _)| (plain.id.clone(), Some(plain)))
+                    .collect();
+                Err(self.abort(e, undo).await)
+            }
+        }
     }
 
-    pub async fn tags(&self, limit: usize) -> Result<Vec<(String, u64)>, AppError> {
+    async fn bulk_staged(
+        &self,
+        targets: &[(ContentEntity, Option<ContentEntity>)],
+        account: &str,
+    ) -> Result<(), StageError> {
+        for (old, updated) in targets {
+            tracing::debug!("bulk id: {}", old.id);
+            match updated {
+                Some(entity) => {
+                    self.replace_staged(entity, old, account, true).await?;
+                }
+                None => {
+                    self.delete_staged(&old.id, Some(old), true).await?;
+                }
+            }
+        }
+
+        self.repositories
+            .unit_of_work()
+            .commit_index()
+            .await
+            .stage(STAGE_COMMIT)?;
+        let ids = targets
+            .iter()
+            .map(|(old, _)| old.id.clone())
+            .collect::<Vec<_>>();
+        self.render_related(&ids).await.stage(STAGE_PAGES)?;
+        self.render_pages().await.stage(STAGE_PAGES)?;
+
+        Ok(())
+    }
+
+    // 公開中の関連記事 (limit 未指定の場合は related_limit 件)
+    pub async fn related(
+        &self,
+        id: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchContentDto>, AppError> {
+        let limit = limit.unwrap_or(CONFIG.content.related_limit).clamp(1, 20);
+        let result = self.repositories.engine().related(id, limit).await?;
+        Ok(result.into_iter().map(SearchContentDto::from).collect())
+    }
+
+    // draft・date_to に合う記事だけを数える
+    pub async fn tags(
+        &self,
+        limit: usize,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, u64)>, AppError> {
         let limit = limit.clamp(1, 100);
-        Ok(self.repositories.engine().top_tags(limit).await?)
+        Ok(self
+            .repositories
+            .engine()
+            .top_tags(limit, draft, date_to)
+            .await?)
     }
 
-    pub async fn caregories(&self, limit: usize) -> Result<Vec<(String, u64)>, AppError> {
+    pub async fn caregories(
+        &self,
+        limit: usize,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, u64)>, AppError> {
         let limit = limit.clamp(1, 100);
-        Ok(self.repositories.engine().top_categories(limit).await?)
+        Ok(self
+            .repositories
+            .engine()
+            .top_categories(limit, draft, date_to)
+            .await?)
+    }
+
+    pub async fn archives(
+        &self,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<ArchiveCountDto>, AppError> {
+        let result = self
+            .repositories
+            .engine()
+            .date_histogram(draft, date_to)
+            .await?;
+        Ok(result.into_iter().map(ArchiveCountDto::from).collect())
+    }
+
+    // 入力中の語句に続くタグ・カテゴリー・タイトルの語句 (記事数の多い順、limit 未指定の場合は 10 件)
+    pub async fn suggest(
+        &self,
+        dto: &SuggestQueryDto,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, u64)>, AppError> {
+        let limit = dto.limit.unwrap_or(10).clamp(1, 50);
+        let result = self
+            .repositories
+            .engine()
+            .suggest(dto.field.into(), &dto.prefix, limit, draft, date_to)
+            .await?;
+        Ok(result)
+    }
+
+    // 公開日時を迎えていない予約投稿 (公開日時の近い順)
+    pub async fn scheduled(&self) -> Result<Vec<SearchContentDto>, AppError> {
+        let params = SearchParams {
+            word: None,
+            draft: Some(false),
+            date_from: Some(Utc::now()),
+            date_to: None,
+            tags: None,
+            categories: None,
+            page: None,
+            per_page: None,
+            sort: None,
+            advanced: false,
+            facets: false,
+        };
+        let mut result = self.repositories.engine().search_all(&params).await?;
+        result.sort_by_key(|content| content.matter.date);
+        Ok(result.into_iter().map(SearchContentDto::from).collect())
+    }
+
+    // 公開日時を迎えた予約投稿の HTML を生成して再登録し、一覧ページ等を再生成する
+    // since 以降 until までに公開日時を迎えた記事のうち HTML が未生成のものが対象 (since が None の場合は全期間)
+    // 公開した件数を返す
+    pub async fn publish_scheduled(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+    ) -> Result<usize, AppError> {
+        let params = SearchParams {
+            word: None,
+            draft: Some(false),
+            date_from: since,
+            date_to: Some(until),
+            tags: None,
+            categories: None,
+            page: None,
+            per_page: None,
+            sort: None,
+            advanced: false,
+            facets: false,
+        };
+
+        let _guard = self.lock.lock().await;
+
+        let mut targets = Vec::new();
+        for plain in self.repositories.engine().search_all(&params).await? {
+            if self.repositories.parser().exists(&plain).await? {
+                continue;
+            }
+            if let Some(entity) = self
+                .repositories
+                .content()
+                .find(&plain.id, &plain.matter.date)
+                .await?
+            {
+                targets.push((plain, entity));
+            }
+        }
+        if targets.is_empty() {
+            return Ok(0);
+        }
+
+        self.repositories.unit_of_work().begin().await?;
+        match self.publish_staged(&targets).await {
+            Ok(()) => {
+                self.repositories.unit_of_work().commit().await?;
+                Ok(targets.len())
+            }
+            Err(e) => {
+                let undo = targets
+                    .into_iter()
+                    .map(|(plain, _)| (plain.id.clone(), Some(plain)))
+                    .collect();
+                Err(self.abort(e, undo).await)
+            }
+        }
+    }
+
+    async fn publish_staged(
+        &self,
+        targets: &[(ContentEntity, ContentEntity)],
+    ) -> Result<(), StageError> {
+        for (_, entity) in targets {
+            tracing::debug!("publish scheduled id: {}", entity.id);
+            let related = self.related_entities(&entity.id).await.stage(STAGE_HTML)?;
+            let html_text = self
+                .repositories
+                .parser()
+                .create(entity, &related)
+                .await
+                .stage(STAGE_HTML)?;
+            let index_entity = ContentEntity {
+                id: entity.id.clone(),
+                matter: entity.matter.clone(),
+                body: html_text,
+            };
+            self.repositories
+                .engine()
+                .edit(&index_entity)
+                .await
+                .stage(STAGE_INDEX)?;
+        }
+
+        self.repositories
+            .unit_of_work()
+            .commit_index()
+            .await
+            .stage(STAGE_COMMIT)?;
+        let ids = targets
+            .iter()
+            .map(|(_, entity)| entity.id.clone())
+            .collect::<Vec<_>>();
+        self.render_related(&ids).await.stage(STAGE_PAGES)?;
+        self.render_pages().await.stage(STAGE_PAGES)?;
+
+        Ok(())
+    }
+
+    pub async fn revisions(&self, id: &str) -> Result<Vec<RevisionDto>, AppError> {
+        let result = self.repositories.revision().list(id).await?;
+        Ok(result.into_iter().map(RevisionDto::summary).collect())
+    }
+
+    pub async fn revision(
+        &self,
+        id: &str,
+        revision: &str,
+    ) -> Result<Option<RevisionDto>, AppError> {
+        let result = self.repositories.revision().find(id, revision).await?;
+        Ok(result.map(RevisionDto::from))
+    }
+
+    pub async fn diff_revisions(
+        &self,
+        id: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<RevisionDiffDto, AppError> {
+        let old = self
+            .repositories
+            .revision()
+            .find(id, from)
+            .await?
+            .ok_or_else(|| AppError::DataNotFound(from.to_string()))?;
+        let new = self
+            .repositories
+            .revision()
+            .find(id, to)
+            .await?
+            .ok_or_else(|| AppError::DataNotFound(to.to_string()))?;
+
+        let lines = line_diff(&revision_text(&old.content), &revision_text(&new.content));
+        let inserted = lines.iter().filter(|l| l.op == DiffOp::Insert).count();
+        let deleted = lines.iter().filter(|l| l.op == DiffOp::Delete).count();
+
+        Ok(RevisionDiffDto {
+            id: id.to_string(),
+            from: RevisionDto::summary(old),
+            to: RevisionDto::summary(new),
+            inserted,
+            deleted,
+            lines,
+        })
+    }
+
+    // 指定したリビジョンの内容で通常の編集を行う (復元自体も新しいリビジョンになる)
+    // if_match が None の場合はバージョンを確認しない
+    pub async fn restore(
+        &self,
+        id: &str,
+        revision: &str,
+        if_match: Option<&IfMatch>,
+        account: &str,
+    ) -> Result<ContentDto, AppError> {
+        // 編集と同じく現在のバージョンの指定を必須にする (上書きする場合は *)
+        let if_match =
+            if_match.ok_or_else(|| AppError::BadRequest("If-Match header is required".into()))?;
+        let entity = self
+            .repositories
+            .revision()
+            .find(id, revision)
+            .await?
+            .ok_or_else(|| AppError::DataNotFound(revision.to_string()))?;
+
+        let dto = ContentDto {
+            id: Some(id.to_string()),
+            ..ContentDto::from(entity.content)
+        };
+        self.edit(&dto, Some(if_match), account).await
+    }
+
+    pub async fn rebuild(&self) -> Result<RebuildReportDto, AppError> {
+        let _guard = self.lock.lock().await;
+
+        let paths = self.repositories.content().list().await?;
+        tracing::debug!("rebuild files: {}", paths.len());
+
+        // 削除と再登録は最後にまとめて確定し、途中の空・作りかけのインデックスを検索させない
+        self.repositories.engine().clear().await?;
+
+        let mut report = RebuildReportDto::default();
+        let mut seen = HashSet::new();
+        let mut slugs = HashSet::new();
+        let mut indexed = Vec::new();
+
+        for path in paths {
+            let mut entity = match self.repositories.content().load(&path).await {
+                Ok(Some(entity)) => entity,
+                Ok(None) => {
+                    report.skipped += 1;
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("rebuild failed to load {}: {}", path, e);
+                    report.failed += 1;
+                    report.errors.push(format!("{}: {}", path, e));
+                    continue;
+                }
+            };
+
+            if !seen.insert(entity.id.clone()) {
+                tracing::warn!("rebuild skipped duplicate id {}: {}", entity.id, path);
+                report.skipped += 1;
+                continue;
+            }
+            // 同じ slug の記事が HTML を上書きし合わないよう、後の記事に連番を付ける
+            if assign_unique_slug(&mut entity.matter.slug, &mut slugs) {
+                tracing::warn!(
+                    "rebuild renamed duplicate slug of {}: {}",
+                    path,
+                    entity.matter.slug.as_deref().unwrap_or_default()
+                );
+            }
+
+            // 関連記事はすべて登録した後に出力し直す
+            let html_text = match self.repositories.parser().create(&entity, &[]).await {
+                Ok(html_text) => html_text,
+                Err(e) => {
+                    tracing::warn!("rebuild failed to render {}: {}", path, e);
+                    report.failed += 1;
+                    report.errors.push(format!("{}: {}", path, e));
+                    continue;
+                }
+            };
+
+            let index_entity = ContentEntity {
+                id: entity.id.clone(),
+                matter: entity.matter.clone(),
+                body: html_text,
+            };
+
+            match self.repositories.engine().create(&index_entity).await {
+                Ok(_) => {
+                    report.processed += 1;
+                    indexed.push((path, entity));
+                }
+                Err(e) => {
+                    tracing::warn!("rebuild failed to index {}: {}", path, e);
+                    report.failed += 1;
+                    report.errors.push(format!("{}: {}", path, e));
+                }
+            }
+        }
+
+        self.repositories.engine().commit().await?;
+
+        if CONFIG.content.related_limit > 0 {
+            for (path, entity) in &indexed {
+                let result = match self.related_entities(&entity.id).await {
+                    Ok(related) => self
+                        .repositories
+                        .parser()
+                        .create(entity, &related)
+                        .await
+                        .map_err(AppError::from),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    tracing::warn!("rebuild failed to render related {}: {}", path, e);
+                    report.errors.push(format!("{}: {}", path, e));
+                }
+            }
+        }
+
+        self.render_pages().await?;
+
+        Ok(report)
+    }
+
+    // Markdown・HTML・検索インデックスの食い違いを調べる (Markdown を正とする)
+    // repair の場合は rebuild と同じく 1 件ずつ修復し、失敗したものは errors に残して続ける
+    pub async fn check(&self, repair: bool) -> Result<CheckReportDto, AppError> {
+        let _guard = self.lock.lock().await;
+        let mut report = CheckReportDto {
+            repaired: repair,
+            ..Default::default()
+        };
+
+        // 検索インデックスの全記事 (下書き・予約投稿を含む)
+        let indexed = self
+            .repositories
+            .engine()
+            .search_all(&no_conditions())
+            .await?
+            .into_iter()
+            .map(|content| (content.id.clone(), content))
+            .collect::<HashMap<_, _>>();
+
+        // インデックスと同じ年月に置かれた Markdown を正しいものとし、それ以外の同じ id の Markdown は古い複製とする
+        let mut sources = Vec::new();
+        let mut unindexed = Vec::new();
+        let mut stale = Vec::new();
+        for path in self.repositories.content().list().await? {
+            match self.repositories.content().load(&path).await {
+                Ok(Some(entity)) => match indexed.get(&entity.id) {
+                    Some(old) if same_month(old, &entity) => sources.push(entity),
+                    _ => unindexed.push((path, entity)),
+                },
+                Ok(None) => report.errors.push(format!(
+                    "{}: misplaced markdown (not in its date directory)",
+                    path
+                )),
+                Err(e) => report.errors.push(format!("{}: {}", path, e)),
+            }
+        }
+        let source_ids = sources
+            .iter()
+            .map(|content| content.id.clone())
+            .collect::<HashSet<_>>();
+        let mut registered = HashSet::new();
+        unindexed.retain(|(path, entity)| {
+            if source_ids.contains(&entity.id) || !registered.insert(entity.id.clone()) {
+                stale.push(path.clone());
+                false
+            } else {
+                true
+            }
+        });
+        report.orphan_markdown = unindexed
+            .iter()
+            .map(|(path, _)| path.clone())
+            .chain(stale.iter().cloned())
+            .collect();
+        report.orphan_markdown.sort();
+
+        // Markdown から出力されるはずの HTML と、出力されてはいけない HTML (下書き・予約投稿)
+        let now = Utc::now();
+        let contents = sources
+            .iter()
+            .chain(unindexed.iter().map(|(_, entity)| entity))
+            .collect::<Vec<_>>();
+        let mut expected = HashSet::new();
+        let mut drafts = Vec::new();
+        for content in &contents {
+            if !content.matter.draft && content.matter.date <= now {
+                expected.insert(self.repositories.parser().content_file(content));
+            } else if self.repositories.parser().exists(content).await? {
+                report
+                    .draft_html
+                    .push(self.repositories.parser().content_file(content));
+                drafts.push(*content);
+            }
+        }
+        report.draft_html.sort();
+
+        report.orphan_html = self
+            .repositories
+            .parser()
+            .list_content_files()
+            .await?
+            .into_iter()
+            .filter(|file| !expected.contains(file) && !report.draft_html.contains(file))
+            .collect();
+
+        let content_ids = contents
+            .iter()
+            .map(|content| content.id.clone())
+            .collect::<HashSet<_>>();
+        report.missing_markdown = indexed
+            .keys()
+            .filter(|id| !content_ids.contains(*id))
+            .cloned()
+            .collect();
+        report.missing_markdown.sort();
+
+        if repair {
+            // インデックスのコミット後に失敗した場合は、削除・置き換えた記事を元に戻す
+            let undo = report
+                .missing_markdown
+                .iter()
+                .chain(unindexed.iter().map(|(_, entity)| &entity.id))
+                .map(|id| (id.clone(), indexed.get(id).cloned()))
+                .collect::<IndexUndo>();
+
+            self.repositories.unit_of_work().begin().await?;
+            match self
+                .repair_staged(&report, &indexed, &unindexed, &stale, &drafts)
+                .await
+            {
+                Ok(changes) => {
+                    self.repositories.unit_of_work().commit().await?;
+                    report.changes = changes;
+                }
+                Err(e) => return Err(self.abort(e, undo).await),
+            }
+        }
+        Ok(report)
+    }
+
+    // check で見つかった食い違いをまとめて修復する (途中で失敗した場合はすべて取り消す)
+    // 行った変更を返す
+    async fn repair_staged(
+        &self,
+        report: &CheckReportDto,
+        indexed: &HashMap<String, ContentEntity>,
+        unindexed: &[(String, ContentEntity)],
+        stale: &[String],
+        drafts: &[&ContentEntity],
+    ) -> Result<Vec<String>, StageError> {
+        let mut changes = Vec::new();
+
+        for id in &report.missing_markdown {
+            self.repositories
+                .engine()
+                .remove(id)
+                .await
+                .stage(STAGE_INDEX)?;
+            changes.push(format!("removed index: {}", id));
+        }
+
+        for path in stale {
+            self.repositories
+                .content()
+                .remove_path(path)
+                .await
+                .stage(STAGE_MARKDOWN)?;
+            changes.push(format!("removed stale markdown: {}", path));
+        }
+
+        // 別の年月でインデックスに登録されている場合は、その登録を置き換える
+        // 他の記事と重なる slug には連番を付ける
+        let mut assigned = HashSet::new();
+        for (path, entity) in unindexed {
+            let mut entity = entity.clone();
+            if let Some(base) = entity.matter.slug.clone() {
+                let slug = self
+                    .unique_slug(&entity.id, base, &assigned)
+                    .await
+                    .stage(STAGE_INDEX)?;
+                assigned.insert(slug.clone());
+                entity.matter.slug = Some(slug);
+            }
+            let html_text = self
+                .repositories
+                .parser()
+                .create(&entity, &[])
+                .await
+                .stage(STAGE_HTML)?;
+            let index_entity = ContentEntity {
+                body: html_text,
+                ..entity.clone()
+            };
+            if indexed.contains_key(&entity.id) {
+                self.repositories
+                    .engine()
+                    .edit(&index_entity)
+                    .await
+                    .stage(STAGE_INDEX)?;
+            } else {
+                self.repositories
+                    .engine()
+                    .create(&index_entity)
+                    .await
+                    .stage(STAGE_INDEX)?;
+            }
+            changes.push(format!("indexed markdown: {}", path));
+        }
+
+        for entity in drafts {
+            self.repositories
+                .parser()
+                .remove(entity)
+                .await
+                .stage(STAGE_HTML)?;
+            changes.push(format!(
+                "removed draft html: {}",
+                self.repositories.parser().content_file(entity)
+            ));
+        }
+
+        for file in &report.orphan_html {
+            self.repositories
+                .parser()
+                .remove_file(file)
+                .await
+                .stage(STAGE_HTML)?;
+            changes.push(format!("removed orphan html: {}", file));
+        }
+
+        if !changes.is_empty() {
+            self.repositories
+                .unit_of_work()
+                .commit_index()
+                .await
+                .stage(STAGE_COMMIT)?;
+            let ids = report
+                .missing_markdown
+                .iter()
+                .cloned()
+                .chain(unindexed.iter().map(|(_, entity)| entity.id.clone()))
+                .chain(drafts.iter().map(|entity| entity.id.clone()))
+                .collect::<Vec<_>>();
+            self.render_related(&ids).await.stage(STAGE_PAGES)?;
+            self.render_pages().await.stage(STAGE_PAGES)?;
+        }
+        Ok(changes)
+    }
+
+    // markdown_dir のファイルを直接編集した場合の反映 (ファイルの監視から呼ぶ)
+    // Markdown は書き換えず、create / edit / remove と同じく HTML・リビジョン・検索インデックスを更新する
+    // 最新のリビジョンと同じ内容の場合はサーバー自身の書き込みとして何もしない
+    // 反映した場合は true を返す
+    pub async fn sync_markdown(&self, path: &str, account: &str) -> Result<bool, AppError> {
+        let _guard = self.lock.lock().await;
+
+        if !self.repositories.content().exists_path(path).await? {
+            return self.sync_removed(path).await;
+        }
+
+        let Some(loaded) = self.repositories.content().load(path).await? else {
+            tracing::warn!("watch skipped misplaced markdown: {}", path);
+            return Ok(false);
+        };
+        // API から保存する場合と同じく正規化する
+        let mut entity = ContentEntity::from(ContentDto::from(loaded));
+        if entity.matter.title.is_empty() || entity.body.is_empty() {
+            tracing::warn!("watch skipped markdown with empty title or body: {}", path);
+            return Ok(false);
+        }
+
+        let latest = self
+            .repositories
+            .revision()
+            .list(&entity.id)
+            .await?
+            .into_iter()
+            .next();
+        if latest.is_some_and(|r| content_version(&r.content) == content_version(&entity)) {
+            return Ok(false);
+        }
+
+        // 他の記事の slug や固定ページと重なる場合は反映しない
+        let resolved = match self.resolve_slug(&entity, true).await {
+            Ok(slug) => self.check_page_conflict(&entity).await.map(|_| slug),
+            Err(e) => Err(e),
+        };
+        entity.matter.slug = match resolved {
+            Ok(slug) => slug,
+            Err(AppError::DataConflict(reason)) => {
+                tracing::warn!("watch skipped conflicting markdown {}: {}", path, reason);
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let id = entity.id.clone();
+        let Some(old) = self.repositories.engine().find(&id).await? else {
+            self.repositories.unit_of_work().begin().await?;
+            return match self.create_staged(&entity, account, false).await {
+                Ok(_) => {
+                    self.repositories.unit_of_work().commit().await?;
+                    tracing::info!("watch created: {}", path);
+                    Ok(true)
+                }
+                Err(e) => Err(self.abort(e, vec![(id, None)]).await),
+            };
+        };
+
+        // 別の年月に置かれた同じ id のファイルは、登録済みのファイルが残っている間は反映しない
+        if !same_month(&old, &entity)
+            && self
+                .repositories
+                .content()
+                .find(&id, &old.matter.date)
+                .await?
+                .is_some()
+        {
+            tracing::warn!("watch skipped duplicate id {}: {}", id, path);
+            return Ok(false);
+        }
+
+        self.repositories.unit_of_work().begin().await?;
+        match self.edit_staged(&entity, &old, account, false).await {
+            Ok(_) => {
+                self.repositories.unit_of_work().commit().await?;
+                tracing::info!("watch edited: {}", path);
+                Ok(true)
+            }
+            Err(e) => Err(self.abort(e, vec![(id, Some(old))]).await),
+        }
+    }
+
+    // 登録済みの記事のファイルが削除された場合のみ記事を削除する
+    // (サーバー自身の削除や、別の年月への移動は登録済みの記事が無いかファイルが残っている)
+    async fn sync_removed(&self, path: &str) -> Result<bool, AppError> {
+        let Some(id) = Path::new(path).file_stem().and_then(|s| s.to_str()) else {
+            return Ok(false);
+        };
+        let Some(old) = self.repositories.engine().find(id).await? else {
+            return Ok(false);
+        };
+        if self
+            .repositories
+            .content()
+            .find(id, &old.matter.date)
+            .await?
+            .is_some()
+        {
+            return Ok(false);
+        }
+
+        self.repositories.unit_of_work().begin().await?;
+        match self.remove_staged(id, Some(&old), false).await {
+            Ok(_) => {
+                self.repositories.unit_of_work().commit().await?;
+                tracing::info!("watch removed: {}", path);
+                Ok(true)
+            }
+            Err(e) => Err(self.abort(e, vec![(id.to_string(), Some(old))]).await),
+        }
+    }
+
+    // 作業単位の変更をすべて取り消し、失敗した段階を返す
+    // 検索インデックスのコミット後に失敗した場合は、インデックスを undo の内容に戻してコミットし直す
+    async fn abort(&self, e: StageError, undo: IndexUndo) -> AppError {
+        tracing::error!("content update failed at stage '{}': {}", e.stage, e.error);
+
+        let mut errors = Vec::new();
+        let undone = if e.stage == STAGE_PAGES {
+            self.undo_index(undo).await
+        } else {
+            Ok(())
+        };
+        if let Err(re) = undone {
+            errors.push(re.to_string());
+        }
+
+        rollback(self.repositories.as_ref(), e, errors).await
+    }
+
+    async fn undo_index(&self, undo: IndexUndo) -> Result<(), AppError> {
+        for (id, old) in undo {
+            match old {
+                Some(old) => {
+                    self.repositories.engine().restore(&old).await?;
+                }
+                None => {
+                    self.repositories.engine().remove(&id).await?;
+                }
+            }
+        }
+        self.repositories.engine().commit().await?;
+        Ok(())
+    }
+
+    // 保存されている内容のバージョンが前提条件と一致するか確認する
+    // 一致しない場合は現在のバージョンを付けて DataConflict
+    async fn check_version(
+        &self,
+        plain: &ContentEntity,
+        if_match: &IfMatch,
+    ) -> Result<(), AppError> {
+        if *if_match == IfMatch::Any {
+            return Ok(());
+        }
+        let current = self
+            .repositories
+            .content()
+            .find(&plain.id, &plain.matter.date)
+            .await?
+            .ok_or_else(|| AppError::DataNotFound(plain.id.clone()))?;
+
+        let version = content_version(&current);
+        if if_match.matches(&version) {
+            Ok(())
+        } else {
+            Err(AppError::VersionMismatch(version))
+        }
+    }
+
+    // slug を決める
+    // 明示的に指定された slug が他の記事で使われている場合は DataConflict
+    // 指定が無い場合はタイトルから生成し、重複する場合は連番を付ける
+    async fn resolve_slug(
+        &self,
+        entity: &ContentEntity,
+        explicit: bool,
+    ) -> Result<Option<String>, AppError> {
+        if explicit {
+            let Some(slug) = entity.matter.slug.clone() else {
+                return Ok(None);
+            };
+            match self.repositories.engine().find_by_slug(&slug).await? {
+                Some(owner) if owner.id != entity.id => Err(AppError::DataConflict(slug)),
+                _ => Ok(Some(slug)),
+            }
+        } else {
+            let Some(base) = slugify(&entity.matter.title) else {
+                return Ok(None);
+            };
+            let slug = self.unique_slug(&entity.id, base, &HashSet::new()).await?;
+            Ok(Some(slug))
+        }
+    }
+
+    // base が他の記事の slug・固定ページの id・assigned と重なる場合は連番を付ける
+    async fn unique_slug(
+        &self,
+        id: &str,
+        base: String,
+        assigned: &HashSet<String>,
+    ) -> Result<String, AppError> {
+        let mut slug = base.clone();
+        let mut n = 1;
+        while assigned.contains(&slug) || self.slug_taken(id, &slug).await? {
+            n += 1;
+            slug = format!("{}-{}", base, n);
+        }
+        Ok(slug)
+    }
+
+    // 他の記事の slug や固定ページの id と重なるか
+    async fn slug_taken(&self, id: &str, slug: &str) -> Result<bool, AppError> {
+        if let Some(owner) = self.repositories.engine().find_by_slug(slug).await? {
+            return Ok(owner.id != id);
+        }
+        Ok(self.repositories.page().find(slug).await?.is_some())
+    }
+
+    // 固定ページと同じ HTML に出力される記事は保存しない
+    async fn check_page_conflict(&self, entity: &ContentEntity) -> Result<(), AppError> {
+        let parser = self.repositories.parser();
+        let file = parser.content_file(entity);
+        let pages = self.repositories.page().list().await?;
+        if pages.iter().any(|page| parser.page_file(page) == file) {
+            return Err(AppError::DataConflict(file));
+        }
+        Ok(())
+    }
+
+    // 保存した内容をリビジョンとして残し、revision_limit を超えた古いものは削除する
+    async fn save_revision(&self, entity: &ContentEntity, account: &str) -> Result<(), AppError> {
+        let created_at = Utc::now();
+        let revision = RevisionEntity {
+            revision: created_at.format("%Y%m%d%H%M%S%6f").to_string(),
+            account: account.to_string(),
+            created_at,
+            content: entity.clone(),
+        };
+        self.repositories.revision().create(&revision).await?;
+        self.repositories
+            .revision()
+            .prune(&entity.id, CONFIG.content.revision_limit)
+            .await?;
+        Ok(())
+    }
+
+    // 静的ページに載せる関連記事
+    // 確定済みの検索インデックスから探すため、新規の記事は render_related で出力し直す
+    async fn related_entities(&self, id: &str) -> Result<Vec<ContentEntity>, AppError> {
+        Ok(self
+            .repositories
+            .engine()
+            .related(id, CONFIG.content.related_limit)
+            .await?)
+    }
+
+    // 確定済みのインデックスから、ids の記事と関連記事が変わりうる公開記事の HTML を出力し直す
+    // 対象は ids の記事自身、ids の記事を関連記事に載せている記事、ids の記事の関連記事
+    // (関連記事の選び方はほぼ対称のため、新たに関連記事に載るのはこれらの記事)
+    async fn render_related(&self, ids: &[String]) -> Result<(), AppError> {
+        if CONFIG.content.related_limit == 0 {
+            return Ok(());
+        }
+
+        let mut targets = ids.iter().cloned().collect::<BTreeSet<_>>();
+        targets.extend(self.repositories.parser().list_related_to(ids).await?);
+        for id in ids {
+            let related = self.related_entities(id).await?;
+            targets.extend(related.into_iter().map(|content| content.id));
+        }
+
+        let now = Utc::now();
+        for id in targets {
+            let Some(indexed) = self.repositories.engine().find(&id).await? else {
+                continue;
+            };
+            if indexed.matter.draft || indexed.matter.date > now {
+                continue;
+            }
+            // インデックスの本文はタグを除いた文字列のため Markdown から出力する
+            let Some(entity) = self
+                .repositories
+                .content()
+                .find(&id, &indexed.matter.date)
+                .await?
+            else {
+                continue;
+            };
+            let related = self.related_entities(&id).await?;
+            self.repositories.parser().create(&entity, &related).await?;
+        }
+        Ok(())
+    }
+
+    // トップページ・一覧ページ・最近の記事を公開記事から再生成する
+    // 公開日時を迎えていない予約投稿は含めない
+    async fn render_pages(&self) -> Result<(), AppError> {
+        let params = SearchParams {
+            word: None,
+            draft: Some(false),
+            date_from: None,
+            date_to: Some(Utc::now()),
+            tags: None,
+            categories: None,
+            page: Some(1),
+            per_page: Some(CONFIG.content.list_per_page),
+            sort: None,
+            advanced: false,
+            facets: false,
+        };
+
+        let first = self.repositories.engine().search(&params).await?;
+        self.repositories.parser().create_index(&first).await?;
+        let max_page = self.render_list(&ArchiveKind::List, &params).await?;
+
+        let recent = self
+            .repositories
+            .engine()
+            .search(&SearchParams {
+                per_page: Some(CONFIG.content.recent_limit),
+                ..params.clone()
+            })
+            .await?;
+        self.repositories.parser().create_recent(&recent).await?;
+
+        let mut pages = vec![(ArchiveKind::List, max_page)];
+        pages.extend(self.render_archives(&params).await?);
+        self.render_feeds(&params).await?;
+
+        let published = self.repositories.engine().search_all(&params).await?;
+        self.repositories
+            .parser()
+            .create_sitemap(&published, &pages)
+            .await?;
+        self.repositories.parser().create_robots().await?;
+        self.render_search_index(published).await?;
+
+        Ok(())
+    }
+
+    // 静的サイト用の検索インデックス (API を使わずにブラウザ側で検索するためのもの)
+    // 本文は分かち書きした語句だけを出力する
+    async fn render_search_index(&self, published: Vec<ContentEntity>) -> Result<(), AppError> {
+        let mut cache = self.search_terms.lock().await;
+
+        // 新規・変更された記事だけを分かち書きする
+        let mut changed = Vec::new();
+        let mut texts = Vec::new();
+        for content in &published {
+            let version = content_version(content);
+            if cache.get(&content.id).map(|(v, _)| v) != Some(&version) {
+                changed.push((content.id.clone(), version));
+                texts.push(content.body.clone());
+            }
+        }
+        let terms = self.repositories.engine().terms(&texts).await?;
+        for ((id, version), terms) in changed.into_iter().zip(terms) {
+            cache.insert(id, (version, terms));
+        }
+
+        let ids = published
+            .iter()
+            .map(|content| content.id.as_str())
+            .collect::<HashSet<_>>();
+        cache.retain(|id, _| ids.contains(id.as_str()));
+
+        let entries = published
+            .into_iter()
+            .map(|content| {
+                let terms = cache
+                    .get(&content.id)
+                    .map(|(_, terms)| terms.clone())
+                    .unwrap_or_default();
+                SearchIndexEntry { content, terms }
+            })
+            .collect::<Vec<_>>();
+        self.repositories
+            .parser()
+            .create_search_index(&entries)
+            .await?;
+        Ok(())
+    }
+
+    // 最新の公開記事から RSS / Atom フィードを再生成する
+    async fn render_feeds(&self, params: &SearchParams) -> Result<(), AppError> {
+        let latest = self
+            .repositories
+            .engine()
+            .search(&SearchParams {
+                per_page: Some(CONFIG.content.feed_limit),
+                ..params.clone()
+            })
+            .await?;
+
+        // フィードには Markdown から変換した本文を載せる
+        let mut contents = Vec::new();
+        for plain in latest.contents {
+            if let Some(content) = self
+                .repositories
+                .content()
+                .find(&plain.id, &plain.matter.date)
+                .await?
+            {
+                contents.push(content);
+            }
+        }
+
+        self.repositories.parser().create_feeds(&contents).await?;
+
+        Ok(())
+    }
+
+    // 公開記事を持つタグ・カテゴリー・年月の一覧ページを再生成し、不要になったページを削除する
+    // 生成した一覧とそのページ数を返す
+    async fn render_archives(
+        &self,
+        params: &SearchParams,
+    ) -> Result<Vec<(ArchiveKind, usize)>, AppError> {
+        let mut kinds = Vec::new();
+        for (tag, _) in self
+            .repositories
+            .engine()
+            .top_tags(usize::MAX, params.draft, params.date_to)
+            .await?
+        {
+            kinds.push(ArchiveKind::Tag(tag));
+        }
+        for (category, _) in self
+            .repositories
+            .engine()
+            .top_categories(usize::MAX, params.draft, params.date_to)
+            .await?
+        {
+            kinds.push(ArchiveKind::Category(category));
+        }
+        for month in self
+            .repositories
+            .engine()
+            .date_histogram(params.draft, params.date_to)
+            .await?
+        {
+            kinds.push(ArchiveKind::Month(month.year, month.month));
+        }
+
+        let mut keep = Vec::new();
+        for kind in kinds {
+            let params = match &kind {
+                ArchiveKind::Tag(tag) => SearchParams {
+                    tags: Some(vec![tag.clone()]),
+                    ..params.clone()
+                },
+                ArchiveKind::Category(category) => SearchParams {
+                    categories: Some(vec![category.clone()]),
+                    ..params.clone()
+                },
+                ArchiveKind::Month(year, month) => {
+                    let Some((from, to)) = month_range(*year, *month) else {
+                        continue;
+                    };
+                    // 当月は公開日時を迎えていない予約投稿を含めない
+                    let to = params.date_to.map_or(to, |date_to| date_to.min(to));
+                    SearchParams {
+                        date_from: Some(from),
+                        date_to: Some(to),
+                        ..params.clone()
+                    }
+                }
+                ArchiveKind::List => continue,
+            };
+            let max_page = self.render_list(&kind, &params).await?;
+            if max_page > 0 {
+                keep.push((kind, max_page));
+            }
+        }
+
+        let kinds = keep
+            .iter()
+            .map(|(kind, _)| kind.clone())
+            .collect::<Vec<_>>();
+        self.repositories.parser().prune_archives(&kinds).await?;
+
+        Ok(keep)
+    }
+
+    // 一覧ページを全ページ分生成し、ページ数を返す(公開記事が無い場合は生成しない)
+    async fn render_list(
+        &self,
+        kind: &ArchiveKind,
+        params: &SearchParams,
+    ) -> Result<usize, AppError> {
+        let mut params = params.clone();
+        params.page = Some(1);
+
+        let first = self.repositories.engine().search(&params).await?;
+        if first.max_page == 0 && *kind != ArchiveKind::List {
+            return Ok(0);
+        }
+        self.repositories.parser().create_list(kind, &first).await?;
+
+        for page in 2..=first.max_page {
+            params.page = Some(page);
+            let result = self.repositories.engine().search(&params).await?;
+            self.repositories
+                .parser()
+                .create_list(kind, &result)
+                .await?;
+        }
+        self.repositories
+            .parser()
+            .prune_list(kind, first.max_page)
+            .await?;
+
+        Ok(first.max_page)
+    }
+}
+
+// 条件なし (全件)
+fn no_conditions() -> SearchParams {
+    SearchParams {
+        word: None,
+        draft: None,
+        date_from: None,
+        date_to: None,
+        tags: None,
+        categories: None,
+        page: None,
+        per_page: None,
+        sort: None,
+        advanced: false,
+        facets: false,
+    }
+}
+
+// Markdown は記事の年月 (YYYYMM) のディレクトリに置かれる
+fn same_month(a: &ContentEntity, b: &ContentEntity) -> bool {
+    a.matter.date.format("%Y%m").to_string() == b.matter.date.format("%Y%m").to_string()
+}
+
+// slug が明示的に指定されているか (空文字は slug の削除)
+fn has_slug(dto: &ContentDto) -> bool {
+    dto.matter.as_ref().is_some_and(|m| m.slug.is_some())
+}
+
+// 指定年月の初日 00:00:00 から末日 23:59:59 までの範囲
+fn month_range(year: i32, month: u32) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let from = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    let to = Utc
+        .with_ymd_and_hms(next_year, next_month, 1, 0, 0, 0)
+        .single()?
+        - Duration::seconds(1);
+    Some((from, to))
+}
+
+// 差分表示用のテキスト (Front Matter 相当 + 本文)
+fn revision_text(content: &ContentEntity) -> String {
+    let matter = &content.matter;
+    format!(
+        "title: {}\ndescription: {}\ndate: {}\ndraft: {}\ntags: {}\ncategories: {}\n---\n{}",
+        matter.title,
+        matter.description.as_deref().unwrap_or_default(),
+        matter.date.to_rfc3339(),
+        matter.draft,
+        matter.tags.join(", "),
+        matter.categories.join(", "),
+        content.body
+    )
+}
+
+// LCS の表の上限 (これを超える場合は変更範囲を全削除 + 全追加として扱う)
+const DIFF_MAX_CELLS: usize = 4_000_000;
+
+// 行単位の差分 (LCS)
+fn line_diff(old: &str, new: &str) -> Vec<DiffLineDto> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    // 先頭と末尾の共通行は LCS の対象から外す
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j] = a[i..] と b[j..] の最長共通部分列の長さ
+    // 上限を超える場合は表を確保しない (空のまま)
+    let mut lcs = Vec::new();
+    if (a.len() + 1).saturating_mul(b.len() + 1) <= DIFF_MAX_CELLS {
+        lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i][j] = if a[i] == b[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+    }
+
+    let line = |op, old_line: Option<usize>, new_line: Option<usize>, text: &str| DiffLineDto {
+        op,
+        old_line: old_line.map(|n| n + 1),
+        new_line: new_line.map(|n| n + 1),
+        text: text.to_string(),
+    };
+
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    for (k, text) in old.iter().take(prefix).enumerate() {
+        lines.push(line(DiffOp::Equal, Some(k), Some(k), text));
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if lcs.is_empty() {
+            // 表を作らなかった場合は削除を先にすべて出す
+            if i < a.len() {
+                lines.push(line(DiffOp::Delete, Some(prefix + i), None, a[i]));
+                i += 1;
+            } else {
+                lines.push(line(DiffOp::Insert, None, Some(prefix + j), b[j]));
+                j += 1;
+            }
+        } else if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push(line(
+                DiffOp::Equal,
+                Some(prefix + i),
+                Some(prefix + j),
+                a[i],
+            ));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(line(DiffOp::Delete, Some(prefix + i), None, a[i]));
+            i += 1;
+        } else {
+            lines.push(line(DiffOp::Insert, None, Some(prefix + j), b[j]));
+            j += 1;
+        }
+    }
+
+    for k in 0..suffix {
+        let (o, n) = (old.len() - suffix + k, new.len() - suffix + k);
+        lines.push(line(DiffOp::Equal, Some(o), Some(n), old[o]));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ops(lines: &[DiffLineDto]) -> Vec<(DiffOp, &str)> {
+        lines
+            .iter()
+            .map(|line| (line.op, line.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn line_diff_identical_inputs() {
+        let lines = line_diff("a\nb\nc", "a\nb\nc");
+        assert_eq!(
+            ops(&lines),
+            vec![
+                (DiffOp::Equal, "a"),
+                (DiffOp::Equal, "b"),
+                (DiffOp::Equal, "c")
+            ]
+        );
+        assert!(line_diff("", "").is_empty());
+    }
+
+    #[test]
+    fn line_diff_shared_prefix_and_suffix_only() {
+        // 変更範囲が空になる (一方が他方の先頭・末尾に行を足しただけ)
+        let lines = line_diff("a\nb", "a\nb\nc");
+        assert_eq!(
+            ops(&lines),
+            vec![
+                (DiffOp::Equal, "a"),
+                (DiffOp::Equal, "b"),
+                (DiffOp::Insert, "c")
+            ]
+        );
+
+        let lines = line_diff("x\na\nb", "a\nb");
+        assert_eq!(
+            ops(&lines),
+            vec![
+                (DiffOp::Delete, "x"),
+                (DiffOp::Equal, "a"),
+                (DiffOp::Equal, "b")
+            ]
+        );
+    }
+
+    #[test]
+    fn line_diff_insert_and_delete_in_middle() {
+        let lines = line_diff("a\nb\nc\nd\ne", "a\nc\nx\nd\ne");
+        assert_eq!(
+            ops(&lines),
+            vec![
+                (DiffOp::Equal, "a"),
+                (DiffOp::Delete, "b"),
+                (DiffOp::Equal, "c"),
+                (DiffOp::Insert, "x"),
+                (DiffOp::Equal, "d"),
+                (DiffOp::Equal, "e")
+            ]
+        );
+    }
+
+    #[test]
+    fn line_diff_numbers_lines_from_one() {
+        let lines = line_diff("a\nb\nc", "a\nx\nc");
+        let numbers = lines
+            .iter()
+            .map(|line| (line.op, line.old_line, line.new_line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            numbers,
+            vec![
+                (DiffOp::Equal, Some(1), Some(1)),
+                (DiffOp::Delete, Some(2), None),
+                (DiffOp::Insert, None, Some(2)),
+                (DiffOp::Equal, Some(3), Some(3))
+            ]
+        );
+    }
+
+    #[test]
+    fn line_diff_falls_back_above_limit() {
+        // 変更範囲が 2001 行ずつの場合は (2001 + 1)^2 > DIFF_MAX_CELLS
+        let old = (0..2001)
+            .map(|i| format!("old {}", i))
+            .chain(["same".to_string()])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let new = (0..2001)
+            .map(|i| format!("new {}", i))
+            .chain(["same".to_string()])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let lines = line_diff(&old, &new);
+
+        assert_eq!(lines.len(), 2001 * 2 + 1);
+        assert!(lines[..2001].iter().all(|line| line.op == DiffOp::Delete));
+        assert!(
+            lines[2001..4002]
+                .iter()
+                .all(|line| line.op == DiffOp::Insert)
+        );
+        assert_eq!(lines[2001].new_line, Some(1));
+        assert_eq!(
+            (lines[4002].op, lines[4002].old_line, lines[4002].new_line),
+            (DiffOp::Equal, Some(2002), Some(2002))
+        );
     }
 }
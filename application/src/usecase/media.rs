@@ -0,0 +1,84 @@
+use crate::{
+    errors::error::AppError,
+    model::media::{MediaDto, MediaUploadDto},
+};
+use config::CONFIG;
+use domain::Repositories;
+use std::sync::Arc;
+
+pub struct MediaUseCase {
+    repositories: Arc<dyn Repositories>,
+}
+
+impl MediaUseCase {
+    pub fn new(repositories: Arc<dyn Repositories>) -> Self {
+        Self { repositories }
+    }
+
+    pub async fn upload(&self, dto: &MediaUploadDto) -> Result<MediaDto, AppError> {
+        // "text/plain; charset=utf-8" のようなパラメータは除いて判定する
+        let content_type = dto
+            .content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        let max_size = if CONFIG.media.image_types.contains(&content_type) {
+            CONFIG.media.image_max_size
+        } else if CONFIG.media.attachment_types.contains(&content_type) {
+            CONFIG.media.attachment_max_size
+        } else {
+            return Err(AppError::BadRequest(format!(
+                "content type '{}' is not allowed",
+                content_type
+            )));
+        };
+
+        if dto.data.is_empty() {
+            return Err(AppError::BadRequest("file is empty".into()));
+        }
+        if dto.data.len() > max_size {
+            return Err(AppError::BadRequest(format!(
+                "file size {} exceeds the limit of {} bytes",
+                dto.data.len(),
+                max_size
+            )));
+        }
+
+        tracing::debug!(
+            "upload media: {} ({}, {} bytes)",
+            dto.file_name,
+            content_type,
+            dto.data.len()
+        );
+
+        let entity = self
+            .repositories
+            .media()
+            .create(&content_type, &dto.file_name, &dto.data)
+            .await?;
+
+        Ok(MediaDto::from(entity))
+    }
+
+    pub async fn list(&self) -> Result<Vec<MediaDto>, AppError> {
+        let entities = self.repositories.media().list().await?;
+        Ok(entities.into_iter().map(MediaDto::from).collect())
+    }
+
+    pub async fn remove(&self, name: &str) -> Result<serde_json::Value, AppError> {
+        tracing::debug!("remove media: {}", name);
+
+        let deleted = self.repositories.media().remove(name).await?;
+        if deleted == 0 {
+            return Err(AppError::DataNotFound(name.to_string()));
+        }
+
+        Ok(serde_json::json!({
+            "name": name,
+            "deleted": deleted
+        }))
+    }
+}
@@ -1,2 +1,5 @@
 pub mod auth;
 pub mod content;
+pub mod media;
+pub mod page;
+mod stage;
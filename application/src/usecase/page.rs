@@ -0,0 +1,236 @@
+use crate::{
+    errors::error::AppError,
+    model::page::{PageDto, PageSummaryDto},
+    usecase::stage::{STAGE_HTML, STAGE_MARKDOWN, Stage, StageError, rollback},
+};
+use domain::{Repositories, model::page::PageEntity};
+use std::{
+    path::{Component, Path},
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+
+// 記事の一覧ページや管理画面と重なる id
+const RESERVED_IDS: [&str; 4] = ["index", "recent", "post", "search"];
+
+pub struct PageUseCase {
+    repositories: Arc<dyn Repositories>,
+    lock: Arc<Mutex<()>>, // 記事・固定ページの変更は同時に1つだけ実行する
+}
+
+impl PageUseCase {
+    pub fn new(repositories: Arc<dyn Repositories>, lock: Arc<Mutex<()>>) -> Self {
+        Self { repositories, lock }
+    }
+
+    pub async fn create(&self, dto: &PageDto) -> Result<PageDto, AppError> {
+        let entity = Self::validate(dto)?;
+
+        let _guard = self.lock.lock().await;
+
+        if self.repositories.page().find(&entity.id).await?.is_some() {
+            return Err(AppError::DataConflict(entity.id));
+        }
+        self.check_file_conflict(&entity).await?;
+
+        self.repositories.unit_of_work().begin().await?;
+        match self.save_staged(&entity, None).await {
+            Ok(()) => {
+                self.repositories.unit_of_work().commit().await?;
+                Ok(PageDto::from(entity))
+            }
+            Err(e) => Err(self.abort(e).await),
+        }
+    }
+
+    pub async fn edit(&self, dto: &PageDto) -> Result<PageDto, AppError> {
+        let id = dto
+            .id
+            .clone()
+            .ok_or_else(|| AppError::BadRequest("id is required".into()))?;
+        let mut entity = Self::validate(dto)?;
+
+        let _guard = self.lock.lock().await;
+
+        let old = match self.repositories.page().find(&id).await? {
+            Some(old) if old.id == entity.id => old,
+            _ => return Err(AppError::DataNotFound(id)),
+        };
+        // 作成日時は変えない
+        entity.matter.date = old.matter.date;
+
+        self.repositories.unit_of_work().begin().await?;
+        match self.save_staged(&entity, Some(&old)).await {
+            Ok(()) => {
+                self.repositories.unit_of_work().commit().await?;
+                Ok(PageDto::from(entity))
+            }
+            Err(e) => Err(self.abort(e).await),
+        }
+    }
+
+    pub async fn remove(&self, id: &str) -> Result<serde_json::Value, AppError> {
+        let _guard = self.lock.lock().await;
+
+        let Some(old) = self.repositories.page().find(id).await? else {
+            return Err(AppError::DataNotFound(id.to_string()));
+        };
+
+        self.repositories.unit_of_work().begin().await?;
+        match self.remove_staged(&old).await {
+            Ok((a, b)) => {
+                self.repositories.unit_of_work().commit().await?;
+                Ok(serde_json::json!({
+                    "id": id,
+                    "html": a,
+                    "markdown": b
+                }))
+            }
+            Err(e) => Err(self.abort(e).await),
+        }
+    }
+
+    // include_drafts が false の場合は公開中の固定ページのみ
+    pub async fn find(&self, id: &str, include_drafts: bool) -> Result<Option<PageDto>, AppError> {
+        let result = self.repositories.page().find(id).await?;
+        Ok(result
+            .filter(|page| include_drafts || !page.matter.draft)
+            .map(PageDto::from))
+    }
+
+    // メニューの並び順
+    pub async fn list(&self, include_drafts: bool) -> Result<Vec<PageSummaryDto>, AppError> {
+        let result = self.repositories.page().list().await?;
+        Ok(result
+            .into_iter()
+            .filter(|page| include_drafts || !page.matter.draft)
+            .map(PageSummaryDto::from)
+            .collect())
+    }
+
+    // すべての固定ページの HTML を出力し直す (下書きの HTML は削除する)
+    pub async fn rebuild(&self) -> Result<usize, AppError> {
+        let _guard = self.lock.lock().await;
+
+        let pages = self.repositories.page().list().await?;
+        for page in &pages {
+            if page.matter.draft {
+                self.repositories.parser().remove_page(page).await?;
+            } else {
+                self.repositories.parser().create_page(page, &pages).await?;
+            }
+        }
+        Ok(pages.len())
+    }
+
+    fn validate(dto: &PageDto) -> Result<PageEntity, AppError> {
+        let entity = dto
+            .to_entity()
+            .ok_or_else(|| AppError::BadRequest("id or title is required".into()))?;
+        if entity.matter.title.is_empty() || entity.body.is_empty() {
+            return Err(AppError::BadRequest("title or body is empty".into()));
+        }
+        if RESERVED_IDS.contains(&entity.id.as_str()) {
+            return Err(AppError::BadRequest(format!(
+                "id '{}' is reserved",
+                entity.id
+            )));
+        }
+        // テンプレートは template_dir 直下のファイル名のみ
+        if let Some(template) = &entity.matter.template {
+            let mut components = Path::new(template).components();
+            if !matches!(
+                (components.next(), components.next()),
+                (Some(Component::Normal(_)), None)
+            ) {
+                return Err(AppError::BadRequest(format!(
+                    "invalid template: {}",
+                    template
+                )));
+            }
+        }
+        Ok(entity)
+    }
+
+    // 出力先に固定ページ以外のファイルがある場合や、記事と URL が重なる場合は作成しない
+    async fn check_file_conflict(&self, entity: &PageEntity) -> Result<(), AppError> {
+        let parser = self.repositories.parser();
+        let file = parser.page_file(entity);
+        if parser.file_exists(&file).await? {
+            return Err(AppError::DataConflict(file));
+        }
+
+        // 未公開の記事は HTML が無いため、permalink に使われる id・slug から探す
+        let engine = self.repositories.engine();
+        let candidates = [
+            engine.find(&entity.id).await?,
+            engine.find_by_slug(&entity.id).await?,
+        ];
+        if candidates
+            .iter()
+            .flatten()
+            .any(|content| parser.content_file(content) == file)
+        {
+            return Err(AppError::DataConflict(file));
+        }
+        Ok(())
+    }
+
+    async fn save_staged(
+        &self,
+        entity: &PageEntity,
+        old: Option<&PageEntity>,
+    ) -> Result<(), StageError> {
+        if let Some(old) = old {
+            self.repositories
+                .parser()
+                .remove_page(old)
+                .await
+                .stage(STAGE_HTML)?;
+        }
+        self.repositories
+            .page()
+            .create(entity)
+            .await
+            .stage(STAGE_MARKDOWN)?;
+
+        self.render_pages().await
+    }
+
+    async fn remove_staged(&self, old: &PageEntity) -> Result<(u64, u64), StageError> {
+        let a = self
+            .repositories
+            .parser()
+            .remove_page(old)
+            .await
+            .stage(STAGE_HTML)?;
+        let b = self
+            .repositories
+            .page()
+            .remove(&old.id)
+            .await
+            .stage(STAGE_MARKDOWN)?;
+
+        self.render_pages().await?;
+
+        Ok((a, b))
+    }
+
+    // メニューが変わるため、固定ページはすべて出力し直す
+    async fn render_pages(&self) -> Result<(), StageError> {
+        let pages = self.repositories.page().list().await.stage(STAGE_HTML)?;
+        for page in &pages {
+            self.repositories
+                .parser()
+                .create_page(page, &pages)
+                .await
+                .stage(STAGE_HTML)?;
+        }
+        Ok(())
+    }
+
+    async fn abort(&self, e: StageError) -> AppError {
+        tracing::error!("page update failed at stage '{}': {}", e.stage, e.error);
+        rollback(self.repositories.as_ref(), e, Vec::new()).await
+    }
+}
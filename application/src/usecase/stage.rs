@@ -0,0 +1,51 @@
+use crate::errors::error::AppError;
+use domain::Repositories;
+
+// 作業単位の段階
+pub(super) const STAGE_MARKDOWN: &str = "markdown";
+pub(super) const STAGE_REVISION: &str = "revision";
+pub(super) const STAGE_HTML: &str = "html";
+pub(super) const STAGE_INDEX: &str = "index";
+pub(super) const STAGE_COMMIT: &str = "commit";
+pub(super) const STAGE_PAGES: &str = "pages";
+
+// 作業単位の中で失敗した段階と原因
+pub(super) struct StageError {
+    pub stage: &'static str,
+    pub error: AppError,
+}
+
+pub(super) trait Stage<T> {
+    fn stage(self, stage: &'static str) -> Result<T, StageError>;
+}
+
+impl<T, E: Into<AppError>> Stage<T> for Result<T, E> {
+    fn stage(self, stage: &'static str) -> Result<T, StageError> {
+        self.map_err(|e| StageError {
+            stage,
+            error: e.into(),
+        })
+    }
+}
+
+// 作業単位の変更をすべて取り消し、失敗した段階を返す
+// errors は取り消しの前に行った後始末で発生したエラー
+pub(super) async fn rollback(
+    repositories: &dyn Repositories,
+    e: StageError,
+    mut errors: Vec<String>,
+) -> AppError {
+    if let Err(re) = repositories.unit_of_work().rollback().await {
+        errors.push(re.to_string());
+    }
+
+    if errors.is_empty() {
+        AppError::Aborted(e.stage.to_string(), Box::new(e.error))
+    } else {
+        AppError::Inconsistent(format!(
+            "rollback failed after stage '{}': {}",
+            e.stage,
+            errors.join(", ")
+        ))
+    }
+}
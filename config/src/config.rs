@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -11,6 +11,7 @@ pub struct Config {
     pub server: ServerConfig,
     pub content: ContentConfig,
     pub search: SearchConfig,
+    pub media: MediaConfig,
     pub security: SecurityConfig,
     pub log: LogConfig,
 }
@@ -39,6 +40,22 @@ pub struct ContentConfig {
     pub template_index: String,     // default "index.html"
     pub template_list: String,      // default "list.html"
     pub template_recent: String,    // default "recent.html"
+    pub list_per_page: usize,       // default 10 (1〜100) clamp
+    pub recent_limit: usize,        // default 5 (1〜50) clamp
+    pub site_url: String,           // default "http://localhost:3000"
+    pub site_title: String,         // default exe basename
+    pub site_description: String,   // default ""
+    pub feed_limit: usize,          // default 20 (1〜100) clamp
+    pub robots_txt: Option<String>, // default None (allow all + sitemap)
+    pub revision_dir: String,       // default "output/.revisions"
+    pub revision_limit: usize,      // default 50 (1〜1000) clamp
+    pub schedule_interval: usize,   // seconds; default 60 (10〜3600) clamp
+    pub permalink: String,          // default "/{year}{month}/{id}.html"
+    pub page_dir: String,           // default "output/pages"
+    pub template_page: String,      // default "page.html"
+    pub related_limit: usize,       // default 5 (0〜20) clamp
+    pub watch: bool,                // default false
+    pub watch_debounce_ms: usize,   // default 500 (100〜10000) clamp
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -48,6 +65,19 @@ pub struct SearchConfig {
     pub index_limit: usize,            // default 3000 (100〜10000) clamp
     pub search_limit: usize,           // default 1000 (100) clamp
     pub memory_budget_in_bytes: usize, // default 50_000_000 (10_000_000〜99_999_999)
+    pub highlight_prefix: String,      // default "<mark>"
+    pub highlight_postfix: String,     // default "</mark>"
+    pub snippet_max_chars: usize,      // default 150 (50〜1000) clamp
+    pub export_shard_size: usize,      // default 262_144 (16_384〜10_000_000) clamp
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MediaConfig {
+    pub dir: String,                   // default "media" (html_dir からの相対パス)
+    pub image_types: Vec<String>, // default ["image/png", "image/jpeg", "image/gif", "image/webp"]
+    pub image_max_size: usize,    // default 5MB (1KB〜50MB) clamp
+    pub attachment_types: Vec<String>, // default ["application/pdf", "application/zip", "text/plain"]
+    pub attachment_max_size: usize,    // default 10MB (1KB〜100MB) clamp
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -92,6 +122,22 @@ impl Default for Config {
                 template_index: "index.html".to_string(),
                 template_list: "list.html".to_string(),
                 template_recent: "recent.html".to_string(),
+                list_per_page: 10,
+                recent_limit: 5,
+                site_url: "http://localhost:3000".to_string(),
+                site_title: exe_name.clone(),
+                site_description: String::new(),
+                feed_limit: 20,
+                robots_txt: None,
+                revision_dir: "output/.revisions".to_string(),
+                revision_limit: 50,
+                schedule_interval: 60,
+                permalink: "/{year}{month}/{id}.html".to_string(),
+                page_dir: "output/pages".to_string(),
+                template_page: "page.html".to_string(),
+                related_limit: 5,
+                watch: false,
+                watch_debounce_ms: 500,
             },
             search: SearchConfig {
                 dictionary_dir: "data/dictionary".to_string(),
@@ -99,6 +145,26 @@ impl Default for Config {
                 index_limit: 3000,
                 search_limit: 1000,
                 memory_budget_in_bytes: 50_000_000,
+                highlight_prefix: "<mark>".to_string(),
+                highlight_postfix: "</mark>".to_string(),
+                snippet_max_chars: 150,
+                export_shard_size: 262_144,
+            },
+            media: MediaConfig {
+                dir: "media".to_string(),
+                image_types: vec![
+                    "image/png".to_string(),
+                    "image/jpeg".to_string(),
+                    "image/gif".to_string(),
+                    "image/webp".to_string(),
+                ],
+                image_max_size: 5 * 1024 * 1024,
+                attachment_types: vec![
+                    "application/pdf".to_string(),
+                    "application/zip".to_string(),
+                    "text/plain".to_string(),
+                ],
+                attachment_max_size: 10 * 1024 * 1024,
             },
             security: SecurityConfig {
                 issuer: exe_name.clone(),
@@ -146,18 +212,19 @@ pub static CONFIG: Lazy<Config> = Lazy::new(|| {
     }
 
     cfg.validate_and_normalize();
-
-    let cli = Cli::parse();
-    cfg.apply_cli(&cli);
+    cfg.apply_cli(&CLI);
 
     cfg
 });
 
+pub static CLI: Lazy<Cli> = Lazy::new(Cli::parse);
+
 #[derive(Debug, Deserialize)]
 struct PartialConfig {
     server: Option<PartialServerConfig>,
     content: Option<PartialContentConfig>,
     search: Option<PartialSearchConfig>,
+    media: Option<PartialMediaConfig>,
     security: Option<PartialSecurityConfig>,
     log: Option<PartialLogConfig>,
 }
@@ -186,6 +253,22 @@ struct PartialContentConfig {
     template_index: Option<String>,
     template_list: Option<String>,
     template_recent: Option<String>,
+    list_per_page: Option<usize>,
+    recent_limit: Option<usize>,
+    site_url: Option<String>,
+    site_title: Option<String>,
+    site_description: Option<String>,
+    feed_limit: Option<usize>,
+    robots_txt: Option<String>,
+    revision_dir: Option<String>,
+    revision_limit: Option<usize>,
+    schedule_interval: Option<usize>,
+    permalink: Option<String>,
+    page_dir: Option<String>,
+    template_page: Option<String>,
+    related_limit: Option<usize>,
+    watch: Option<bool>,
+    watch_debounce_ms: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -195,6 +278,19 @@ struct PartialSearchConfig {
     max_index_count: Option<usize>,
     search_limit: Option<usize>,
     memory_budget_in_bytes: Option<usize>,
+    highlight_prefix: Option<String>,
+    highlight_postfix: Option<String>,
+    snippet_max_chars: Option<usize>,
+    export_shard_size: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PartialMediaConfig {
+    dir: Option<String>,
+    image_types: Option<Vec<String>>,
+    image_max_size: Option<usize>,
+    attachment_types: Option<Vec<String>>,
+    attachment_max_size: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -237,6 +333,22 @@ impl Config {
             if let Some(v) = content.template_index { self.content.template_index = v; }
             if let Some(v) = content.template_list { self.content.template_list = v; }
             if let Some(v) = content.template_recent { self.content.template_recent = v; }
+            if let Some(v) = content.list_per_page { self.content.list_per_page = v; }
+            if let Some(v) = content.recent_limit { self.content.recent_limit = v; }
+            if let Some(v) = content.site_url { self.content.site_url = v; }
+            if let Some(v) = content.site_title { self.content.site_title = v; }
+            if let Some(v) = content.site_description { self.content.site_description = v; }
+            if let Some(v) = content.feed_limit { self.content.feed_limit = v; }
+            if let Some(v) = content.robots_txt { self.content.robots_txt = Some(v); }
+            if let Some(v) = content.revision_dir { self.content.revision_dir = v; }
+            if let Some(v) = content.revision_limit { self.content.revision_limit = v; }
+            if let Some(v) = content.schedule_interval { self.content.schedule_interval = v; }
+            if let Some(v) = content.permalink { self.content.permalink = v; }
+            if let Some(v) = content.page_dir { self.content.page_dir = v; }
+            if let Some(v) = content.template_page { self.content.template_page = v; }
+            if let Some(v) = content.related_limit { self.content.related_limit = v; }
+            if let Some(v) = content.watch { self.content.watch = v; }
+            if let Some(v) = content.watch_debounce_ms { self.content.watch_debounce_ms = v; }
         }
         if let Some(search) = p.search {
             if let Some(v) = search.dictionary_dir { self.search.dictionary_dir = v; }
@@ -244,6 +356,17 @@ impl Config {
             if let Some(v) = search.max_index_count { self.search.index_limit = v; }
             if let Some(v) = search.search_limit { self.search.search_limit = v; }
             if let Some(v) = search.memory_budget_in_bytes { self.search.memory_budget_in_bytes = v; }
+            if let Some(v) = search.highlight_prefix { self.search.highlight_prefix = v; }
+            if let Some(v) = search.highlight_postfix { self.search.highlight_postfix = v; }
+            if let Some(v) = search.snippet_max_chars { self.search.snippet_max_chars = v; }
+            if let Some(v) = search.export_shard_size { self.search.export_shard_size = v; }
+        }
+        if let Some(media) = p.media {
+            if let Some(v) = media.dir { self.media.dir = v; }
+            if let Some(v) = media.image_types { self.media.image_types = v; }
+            if let Some(v) = media.image_max_size { self.media.image_max_size = v; }
+            if let Some(v) = media.attachment_types { self.media.attachment_types = v; }
+            if let Some(v) = media.attachment_max_size { self.media.attachment_max_size = v; }
         }
         if let Some(security) = p.security {
             if let Some(v) = security.issuer { self.security.issuer = v; }
@@ -255,30 +378,28 @@ impl Config {
             if let Some(v) = security.update_interval { self.security.update_interval = v; }
             if let Some(v) = security.allow_signup { self.security.allow_signup = v; }
         }
-        if let Some(log) = p.log {
-            if let Some(v) = log.level { self.log.level = Some(v); }
-        }
+        if let Some(v) = p.log.and_then(|log| log.level) { self.log.level = Some(v); }
     }
 
     fn validate_and_normalize(&mut self) {
-        if let Some(ref level) = self.log.level {
-            if EnvFilter::try_new(level).is_err() {
-                eprintln!(
-                    "Invalid log filter expression: '{}'. Logging will be disabled.",
-                    level
-                );
-                self.log.level = None;
-            }
+        if let Some(ref level) = self.log.level
+            && EnvFilter::try_new(level).is_err()
+        {
+            eprintln!(
+                "Invalid log filter expression: '{}'. Logging will be disabled.",
+                level
+            );
+            self.log.level = None;
         }
 
-        if let Some(ref dir) = self.server.static_dir {
-            if !Path::new(dir).is_dir() {
-                eprintln!(
-                    "Static directory '{}' does not exist. Static serving will be disabled.",
-                    dir
-                );
-                self.server.static_dir = None;
-            }
+        if let Some(ref dir) = self.server.static_dir
+            && !Path::new(dir).is_dir()
+        {
+            eprintln!(
+                "Static directory '{}' does not exist. Static serving will be disabled.",
+                dir
+            );
+            self.server.static_dir = None;
         }
 
         for (name, path) in &[
@@ -393,6 +514,122 @@ impl Config {
             self.content.max_categories = cat_new;
         }
 
+        // list per page
+        let (lp_old, lp_new) = (
+            self.content.list_per_page,
+            clamp_usize(self.content.list_per_page, 1, 100),
+        );
+        if lp_old != lp_new {
+            eprintln!(
+                "list_per_page {} is out of range [1,100], rounded to {}.",
+                lp_old, lp_new
+            );
+            self.content.list_per_page = lp_new;
+        }
+
+        // recent limit
+        let (rl_old, rl_new) = (
+            self.content.recent_limit,
+            clamp_usize(self.content.recent_limit, 1, 50),
+        );
+        if rl_old != rl_new {
+            eprintln!(
+                "recent_limit {} is out of range [1,50], rounded to {}.",
+                rl_old, rl_new
+            );
+            self.content.recent_limit = rl_new;
+        }
+
+        // feed limit
+        let (fl_old, fl_new) = (
+            self.content.feed_limit,
+            clamp_usize(self.content.feed_limit, 1, 100),
+        );
+        if fl_old != fl_new {
+            eprintln!(
+                "feed_limit {} is out of range [1,100], rounded to {}.",
+                fl_old, fl_new
+            );
+            self.content.feed_limit = fl_new;
+        }
+
+        // revision limit
+        let (rv_old, rv_new) = (
+            self.content.revision_limit,
+            clamp_usize(self.content.revision_limit, 1, 1000),
+        );
+        if rv_old != rv_new {
+            eprintln!(
+                "revision_limit {} is out of range [1,1000], rounded to {}.",
+                rv_old, rv_new
+            );
+            self.content.revision_limit = rv_new;
+        }
+
+        // schedule interval
+        let (si_old, si_new) = (
+            self.content.schedule_interval,
+            clamp_usize(self.content.schedule_interval, 10, 3600),
+        );
+        if si_old != si_new {
+            eprintln!(
+                "schedule_interval {} is out of range [10,3600], rounded to {}.",
+                si_old, si_new
+            );
+            self.content.schedule_interval = si_new;
+        }
+
+        // related limit
+        let (rel_old, rel_new) = (
+            self.content.related_limit,
+            clamp_usize(self.content.related_limit, 0, 20),
+        );
+        if rel_old != rel_new {
+            eprintln!(
+                "related_limit {} is out of range [0,20], rounded to {}.",
+                rel_old, rel_new
+            );
+            self.content.related_limit = rel_new;
+        }
+
+        // watch debounce
+        let (wd_old, wd_new) = (
+            self.content.watch_debounce_ms,
+            clamp_usize(self.content.watch_debounce_ms, 100, 10_000),
+        );
+        if wd_old != wd_new {
+            eprintln!(
+                "watch_debounce_ms {} is out of range [100,10000], rounded to {}.",
+                wd_old, wd_new
+            );
+            self.content.watch_debounce_ms = wd_new;
+        }
+
+        // permalink ({id} か {slug} が必須、先頭は /)
+        if !self.content.permalink.contains("{id}") && !self.content.permalink.contains("{slug}") {
+            panic!(
+                "Configured permalink '{}' must contain {{id}} or {{slug}}.",
+                self.content.permalink
+            );
+        }
+        if self
+            .content
+            .permalink
+            .split('/')
+            .any(|s| s == "." || s == "..")
+        {
+            panic!(
+                "Configured permalink '{}' must not contain '.' or '..' segments.",
+                self.content.permalink
+            );
+        }
+        if !self.content.permalink.starts_with('/') {
+            self.content.permalink.insert(0, '/');
+        }
+
+        // site url (末尾の / は付けない)
+        self.content.site_url = self.content.site_url.trim_end_matches('/').to_string();
+
         // index limit
         let (il_old, il_new) = (
             self.search.index_limit,
@@ -432,6 +669,69 @@ impl Config {
             self.search.memory_budget_in_bytes = mb_new;
         }
 
+        // snippet max chars
+        let (sm_old, sm_new) = (
+            self.search.snippet_max_chars,
+            clamp_usize(self.search.snippet_max_chars, 50, 1000),
+        );
+        if sm_old != sm_new {
+            eprintln!(
+                "snippet_max_chars {} out of range [50,1000], rounded to {}.",
+                sm_old, sm_new
+            );
+            self.search.snippet_max_chars = sm_new;
+        }
+
+        // export shard size
+        let (es_old, es_new) = (
+            self.search.export_shard_size,
+            clamp_usize(self.search.export_shard_size, 16_384, 10_000_000),
+        );
+        if es_old != es_new {
+            eprintln!(
+                "export_shard_size {} out of range [16384,10000000], rounded to {}.",
+                es_old, es_new
+            );
+            self.search.export_shard_size = es_new;
+        }
+
+        // Media
+        let media_dir = Path::new(&self.media.dir);
+        if media_dir.is_absolute()
+            || media_dir
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        {
+            panic!(
+                "Configured media.dir '{}' must be a relative path inside html_dir.",
+                self.media.dir
+            );
+        }
+
+        let (im_old, im_new) = (
+            self.media.image_max_size,
+            clamp_usize(self.media.image_max_size, 1024, 50 * 1024 * 1024),
+        );
+        if im_old != im_new {
+            eprintln!(
+                "media.image_max_size {} out of range [1KB,50MB], rounded to {}.",
+                im_old, im_new
+            );
+            self.media.image_max_size = im_new;
+        }
+
+        let (am_old, am_new) = (
+            self.media.attachment_max_size,
+            clamp_usize(self.media.attachment_max_size, 1024, 100 * 1024 * 1024),
+        );
+        if am_old != am_new {
+            eprintln!(
+                "media.attachment_max_size {} out of range [1KB,100MB], rounded to {}.",
+                am_old, am_new
+            );
+            self.media.attachment_max_size = am_new;
+        }
+
         // Security: expire clamp (180 ..= 90 days)
         let expire_clamp = clamp_i64(self.security.expire, 180, 60 * 60 * 24 * 90);
         if expire_clamp != self.security.expire {
@@ -511,6 +811,19 @@ impl Config {
             (false, false) => {}
         }
 
+        // content
+        match (cli.watch, cli.no_watch) {
+            (true, true) => {
+                eprintln!(
+                    "CLI: both --watch and --no-watch specified. --no-watch will take precedence."
+                );
+                self.content.watch = false;
+            }
+            (true, false) => self.content.watch = true,
+            (false, true) => self.content.watch = false,
+            (false, false) => {}
+        }
+
         // log
         if cli.no_log {
             self.log.level = None;
@@ -576,4 +889,24 @@ pub struct Cli {
     pub allow_signup: bool,
     #[arg(long)]
     pub no_allow_signup: bool,
+
+    #[arg(long)]
+    pub watch: bool,
+    #[arg(long)]
+    pub no_watch: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Rebuild the search index and all HTML from markdown_dir
+    Rebuild,
+    /// Check markdown_dir, html_dir and the search index for inconsistencies
+    Check {
+        /// Fix the inconsistencies found (markdown_dir is treated as the source of truth)
+        #[arg(long)]
+        repair: bool,
+    },
 }
@@ -1,2 +1,2 @@
 mod config;
-pub use config::CONFIG;
+pub use config::{CLI, CONFIG, Command};
@@ -0,0 +1,7 @@
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ArchiveKind {
+    List,             // 記事一覧
+    Tag(String),      // タグ別一覧
+    Category(String), // カテゴリー別一覧
+    Month(i32, u32),  // 年月別一覧
+}
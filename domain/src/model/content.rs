@@ -1,5 +1,44 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const SLUG_MAX_LEN: usize = 80;
+
+// URL に使える形に変換する (英数字以外は - に置き換え、英字は小文字にする)
+// 空になる場合は None
+pub fn slugify(text: &str) -> Option<String> {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug
+        .chars()
+        .take(SLUG_MAX_LEN)
+        .collect::<String>()
+        .trim_end_matches('-')
+        .to_string();
+    if slug.is_empty() { None } else { Some(slug) }
+}
+
+// used の slug と重なる場合は連番を付けて used に加える (変更した場合は true)
+pub fn assign_unique_slug(slug: &mut Option<String>, used: &mut HashSet<String>) -> bool {
+    let Some(base) = slug.clone() else {
+        return false;
+    };
+    let mut unique = base.clone();
+    let mut n = 1;
+    while !used.insert(unique.clone()) {
+        n += 1;
+        unique = format!("{}-{}", base, n);
+    }
+    *slug = Some(unique);
+    n > 1
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FrontMatterEntity {
@@ -9,6 +48,8 @@ pub struct FrontMatterEntity {
     pub draft: bool,
     pub tags: Vec<String>,
     pub categories: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
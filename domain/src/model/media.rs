@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaEntity {
+    pub name: String, // media ディレクトリからの相対パス (例: 202510/xxxx.png)
+    pub url: String,
+    pub content_type: String,
+    pub size: u64,
+    pub updated_at: DateTime<Utc>,
+}
@@ -35,11 +35,11 @@ impl MemberEntity {
     }
 
     pub fn is_locked(&self, lock_threshold: i64, lock_seconds: i64) -> bool {
-        if let Some(last_failed) = self.last_failed_at {
-            if self.failed_attempts >= lock_threshold {
-                let lock_duration = Duration::seconds(lock_seconds * self.failed_attempts);
-                return Utc::now() < last_failed + lock_duration;
-            }
+        if let Some(last_failed) = self.last_failed_at
+            && self.failed_attempts >= lock_threshold
+        {
+            let lock_duration = Duration::seconds(lock_seconds * self.failed_attempts);
+            return Utc::now() < last_failed + lock_duration;
         }
         false
     }
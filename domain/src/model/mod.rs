@@ -1,3 +1,7 @@
+pub mod archive;
 pub mod content;
+pub mod media;
 pub mod member;
+pub mod page;
+pub mod revision;
 pub mod search_engine;
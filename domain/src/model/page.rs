@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageMatterEntity {
+    pub date: DateTime<Utc>, // 作成日時
+    pub title: String,
+    pub description: Option<String>,
+    pub draft: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>, // 未指定の場合は template_page
+    #[serde(default)]
+    pub order: i32, // メニューの並び順 (昇順)
+}
+
+// 固定ページ (記事と違い日付ごとのディレクトリを持たず、フィード・一覧・検索の対象外)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageEntity {
+    pub id: String, // URL にも使う (/{id}.html)
+    pub matter: PageMatterEntity,
+    pub body: String,
+}
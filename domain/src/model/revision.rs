@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::model::content::ContentEntity;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevisionEntity {
+    pub revision: String, // 保存日時から生成 (例: 20251001123456000000)
+    pub account: String,
+    pub created_at: DateTime<Utc>,
+    pub content: ContentEntity,
+}
@@ -10,6 +10,18 @@ pub struct SearchParams {
     pub categories: Option<Vec<String>>, // 各値に完全一致
     pub page: Option<usize>,  // ページ
     pub per_page: Option<usize>, // ページ内行数
+    pub sort: Option<SearchSort>, // 並び順 (未指定の場合は DateDesc)
+    pub advanced: bool,       // word を高度な検索構文として扱う
+    pub facets: bool,         // 項目ごとの件数 (SearchFacets) を集計する (API からの検索のみ)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchSort {
+    Relevance, // 検索語との関連度の高い順 (word が無い場合は DateDesc)
+    #[default]
+    DateDesc,
+    DateAsc,
+    Title, // タイトルの昇順
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -18,5 +30,66 @@ pub struct SearchResult {
     pub per_page: usize,
     pub max_page: usize,
     pub overflow: bool,
+    pub total: usize, // search_limit で切り詰める前の件数
     pub contents: Vec<ContentEntity>,
+    pub highlights: Vec<SearchHighlight>, // word を指定した場合のみ
+    pub facets: SearchFacets,
+}
+
+// 検索条件に一致するすべての記事の項目ごとの件数
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchFacets {
+    pub tags: Vec<(String, u64)>,       // 件数の多い順
+    pub categories: Vec<(String, u64)>, // 件数の多い順
+    pub draft: Vec<(bool, u64)>,
+    pub months: Vec<MonthCount>, // 新しい年月順
 }
+
+// 静的サイト用の検索インデックスに出力する記事と、本文を分かち書きした語句
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchIndexEntry {
+    pub content: ContentEntity,
+    pub terms: Vec<String>, // 重複を除いて昇順 (英字は小文字)
+}
+
+// 検索語を強調したタイトル・本文の抜粋 (一致しなかったフィールドは None)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHighlight {
+    pub id: String,
+    pub title: Option<String>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthCount {
+    pub year: i32,
+    pub month: u32,
+    pub count: u64,
+}
+
+// 入力候補の対象 (title は分かち書きした語句)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestField {
+    Tags,
+    Categories,
+    Title,
+}
+
+// 検索語の構文エラー (position は先頭を 1 とする文字の位置)
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuerySyntaxError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for QuerySyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid query at position {}: {}",
+            self.position, self.message
+        )
+    }
+}
+
+impl std::error::Error for QuerySyntaxError {}
@@ -1,11 +1,19 @@
 use crate::repository::content::ContentRepository;
 use crate::repository::html_parser::HtmlParserRepository;
+use crate::repository::media::MediaRepository;
 use crate::repository::member::MemberRepository;
+use crate::repository::page::PageRepository;
+use crate::repository::revision::RevisionRepository;
 use crate::repository::search_engine::SearchEngineRepository;
+use crate::repository::unit_of_work::UnitOfWorkRepository;
 
 pub trait Repositories: Send + Sync {
-    fn engine<'s>(&'s self) -> &'s dyn SearchEngineRepository;
-    fn parser<'s>(&'s self) -> &'s dyn HtmlParserRepository;
-    fn content<'s>(&'s self) -> &'s dyn ContentRepository;
-    fn member<'s>(&'s self) -> &'s dyn MemberRepository;
+    fn engine(&self) -> &dyn SearchEngineRepository;
+    fn parser(&self) -> &dyn HtmlParserRepository;
+    fn content(&self) -> &dyn ContentRepository;
+    fn member(&self) -> &dyn MemberRepository;
+    fn media(&self) -> &dyn MediaRepository;
+    fn revision(&self) -> &dyn RevisionRepository;
+    fn page(&self) -> &dyn PageRepository;
+    fn unit_of_work(&self) -> &dyn UnitOfWorkRepository;
 }
@@ -10,4 +10,8 @@ pub trait ContentRepository: Send + Sync {
     async fn create(&self, entity: &ContentEntity) -> Result<ContentEntity, BoxError>;
     async fn find(&self, id: &str, date: &DateTime<Utc>) -> Result<Option<ContentEntity>, BoxError>;
     async fn remove(&self, id: &str) -> Result<u64, BoxError>;
+    async fn list(&self) -> Result<Vec<String>, BoxError>;
+    async fn load(&self, path: &str) -> Result<Option<ContentEntity>, BoxError>;
+    async fn remove_path(&self, path: &str) -> Result<bool, BoxError>;
+    async fn exists_path(&self, path: &str) -> Result<bool, BoxError>;
 }
@@ -1,11 +1,36 @@
 use async_trait::async_trait;
 
-use crate::model::content::ContentEntity;
+use crate::model::{
+    archive::ArchiveKind,
+    content::ContentEntity,
+    page::PageEntity,
+    search_engine::{SearchIndexEntry, SearchResult},
+};
 use common::types::BoxError;
 
 #[rustfmt::skip]
 #[async_trait]
 pub trait HtmlParserRepository: Send + Sync {
-    async fn create(&self, entity: &ContentEntity) -> Result<String, BoxError>;
-    async fn remove(&self, id: &str) -> Result<u64, BoxError>;
+    async fn create(&self, entity: &ContentEntity, related: &[ContentEntity]) -> Result<String, BoxError>;
+    async fn exists(&self, entity: &ContentEntity) -> Result<bool, BoxError>;
+    fn content_file(&self, entity: &ContentEntity) -> String;
+    async fn list_content_files(&self) -> Result<Vec<String>, BoxError>;
+    async fn list_related_to(&self, ids: &[String]) -> Result<Vec<String>, BoxError>;
+    async fn file_exists(&self, file: &str) -> Result<bool, BoxError>;
+    async fn remove_file(&self, file: &str) -> Result<bool, BoxError>;
+    async fn remove(&self, entity: &ContentEntity) -> Result<u64, BoxError>;
+    async fn create_redirect(&self, from: &ContentEntity, to: &ContentEntity) -> Result<(), BoxError>;
+    async fn remove_redirects(&self, id: &str) -> Result<u64, BoxError>;
+    async fn create_index(&self, result: &SearchResult) -> Result<(), BoxError>;
+    async fn create_list(&self, kind: &ArchiveKind, result: &SearchResult) -> Result<(), BoxError>;
+    async fn prune_list(&self, kind: &ArchiveKind, max_page: usize) -> Result<u64, BoxError>;
+    async fn prune_archives(&self, keep: &[ArchiveKind]) -> Result<u64, BoxError>;
+    async fn create_recent(&self, result: &SearchResult) -> Result<(), BoxError>;
+    async fn create_feeds(&self, contents: &[ContentEntity]) -> Result<(), BoxError>;
+    async fn create_sitemap(&self, contents: &[ContentEntity], pages: &[(ArchiveKind, usize)]) -> Result<(), BoxError>;
+    async fn create_robots(&self) -> Result<(), BoxError>;
+    async fn create_search_index(&self, entries: &[SearchIndexEntry]) -> Result<(), BoxError>;
+    fn page_file(&self, entity: &PageEntity) -> String;
+    async fn create_page(&self, entity: &PageEntity, menu: &[PageEntity]) -> Result<(), BoxError>;
+    async fn remove_page(&self, entity: &PageEntity) -> Result<u64, BoxError>;
 }
@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+
+use crate::model::media::MediaEntity;
+use common::types::BoxError;
+
+#[rustfmt::skip]
+#[async_trait]
+pub trait MediaRepository: Send + Sync {
+    async fn create(&self, content_type: &str, file_name: &str, data: &[u8]) -> Result<MediaEntity, BoxError>;
+    async fn list(&self) -> Result<Vec<MediaEntity>, BoxError>;
+    async fn remove(&self, name: &str) -> Result<u64, BoxError>;
+}
@@ -1,4 +1,8 @@
 pub mod content;
 pub mod html_parser;
+pub mod media;
 pub mod member;
+pub mod page;
+pub mod revision;
 pub mod search_engine;
+pub mod unit_of_work;
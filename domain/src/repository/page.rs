@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+use crate::model::page::PageEntity;
+use common::types::BoxError;
+
+#[rustfmt::skip]
+#[async_trait]
+pub trait PageRepository: Send + Sync {
+    async fn create(&self, entity: &PageEntity) -> Result<PageEntity, BoxError>;
+    async fn find(&self, id: &str) -> Result<Option<PageEntity>, BoxError>;
+    async fn list(&self) -> Result<Vec<PageEntity>, BoxError>;
+    async fn remove(&self, id: &str) -> Result<u64, BoxError>;
+}
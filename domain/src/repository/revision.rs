@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+use crate::model::revision::RevisionEntity;
+use common::types::BoxError;
+
+#[rustfmt::skip]
+#[async_trait]
+pub trait RevisionRepository: Send + Sync {
+    async fn create(&self, entity: &RevisionEntity) -> Result<RevisionEntity, BoxError>;
+    async fn find(&self, id: &str, revision: &str) -> Result<Option<RevisionEntity>, BoxError>;
+    async fn list(&self, id: &str) -> Result<Vec<RevisionEntity>, BoxError>;
+    async fn prune(&self, id: &str, keep: usize) -> Result<u64, BoxError>;
+}
@@ -1,9 +1,10 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 #[allow(unused_imports)]
 use crate::model::{
     content::ContentEntity,
-    search_engine::{SearchParams, SearchResult},
+    search_engine::{MonthCount, SearchParams, SearchResult, SuggestField},
 };
 use common::types::BoxError;
 
@@ -12,10 +13,39 @@ pub trait SearchEngineRepository: Send + Sync {
     async fn create(&self, params: &ContentEntity) -> Result<ContentEntity, BoxError>;
     async fn edit(&self, params: &ContentEntity) -> Result<Option<ContentEntity>, BoxError>;
     async fn remove(&self, id: &str) -> Result<usize, BoxError>;
+    async fn restore(&self, params: &ContentEntity) -> Result<ContentEntity, BoxError>;
+    async fn clear(&self) -> Result<(), BoxError>;
     async fn commit(&self) -> Result<(), BoxError>;
     async fn count(&self, id: &str) -> Result<usize, BoxError>;
     async fn find(&self, id: &str) -> Result<Option<ContentEntity>, BoxError>;
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<ContentEntity>, BoxError>;
     async fn search(&self, params: &SearchParams) -> Result<SearchResult, BoxError>;
-    async fn top_tags(&self, limit: usize) -> Result<Vec<(String, u64)>, BoxError>;
-    async fn top_categories(&self, limit: usize) -> Result<Vec<(String, u64)>, BoxError>;
+    async fn search_all(&self, params: &SearchParams) -> Result<Vec<ContentEntity>, BoxError>;
+    async fn related(&self, id: &str, limit: usize) -> Result<Vec<ContentEntity>, BoxError>;
+    async fn terms(&self, texts: &[String]) -> Result<Vec<Vec<String>>, BoxError>;
+    async fn top_tags(
+        &self,
+        limit: usize,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, u64)>, BoxError>;
+    async fn top_categories(
+        &self,
+        limit: usize,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, u64)>, BoxError>;
+    async fn date_histogram(
+        &self,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<MonthCount>, BoxError>;
+    async fn suggest(
+        &self,
+        field: SuggestField,
+        prefix: &str,
+        limit: usize,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, u64)>, BoxError>;
 }
@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+use common::types::BoxError;
+
+// 記事の Markdown・HTML・リビジョン・検索インデックスの変更をまとめて確定・取り消しする
+#[rustfmt::skip]
+#[async_trait]
+pub trait UnitOfWorkRepository: Send + Sync {
+    async fn begin(&self) -> Result<(), BoxError>;
+    async fn commit_index(&self) -> Result<(), BoxError>;
+    async fn commit(&self) -> Result<(), BoxError>;
+    async fn rollback(&self) -> Result<(), BoxError>;
+}
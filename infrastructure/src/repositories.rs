@@ -1,13 +1,19 @@
+use std::{path::PathBuf, sync::Arc};
+
 use crate::repository::{
-    content::ContentRepositoryImpl, html_parser::HtmlParserRepositoryImpl,
-    member::MemberRepositoryImpl, search_engine::SearchEngineRepositoryImpl,
+    content::ContentRepositoryImpl, html_parser::HtmlParserRepositoryImpl, journal::FileJournal,
+    media::MediaRepositoryImpl, member::MemberRepositoryImpl, page::PageRepositoryImpl,
+    revision::RevisionRepositoryImpl, search_engine::SearchEngineRepositoryImpl,
+    unit_of_work::UnitOfWorkRepositoryImpl,
 };
 use common::types::BoxError;
+use config::CONFIG;
 use domain::{
     Repositories,
     repository::{
-        content::ContentRepository, html_parser::HtmlParserRepository, member::MemberRepository,
-        search_engine::SearchEngineRepository,
+        content::ContentRepository, html_parser::HtmlParserRepository, media::MediaRepository,
+        member::MemberRepository, page::PageRepository, revision::RevisionRepository,
+        search_engine::SearchEngineRepository, unit_of_work::UnitOfWorkRepository,
     },
 };
 
@@ -17,38 +23,75 @@ pub struct RepositoriesImpl {
     parser_repo: HtmlParserRepositoryImpl,
     content_repo: ContentRepositoryImpl,
     member_repo: MemberRepositoryImpl,
+    media_repo: MediaRepositoryImpl,
+    revision_repo: RevisionRepositoryImpl,
+    unit_of_work_repo: UnitOfWorkRepositoryImpl,
+    page_repo: PageRepositoryImpl,
 }
 
 impl RepositoriesImpl {
-    pub fn new() -> Result<Self, BoxError> {
-        let engine_repo = SearchEngineRepositoryImpl::new()?;
-        let parser_repo = HtmlParserRepositoryImpl::new();
-        let content_repo = ContentRepositoryImpl::new();
+    pub async fn new() -> Result<Self, BoxError> {
+        // 前回の処理中に停止していた場合は、検索インデックスのコミット有無に合わせて復旧する
+        let journal = Arc::new(FileJournal::new(
+            PathBuf::from(&CONFIG.content.markdown_dir).join(".journal.json"),
+        ));
+        let committed = SearchEngineRepositoryImpl::startup_commit_payload()?;
+        journal.recover(|id| committed.as_deref() == Some(id))?;
+
+        // スキーマの版が変わった場合は、復旧後の Markdown から検索インデックスを作り直す
+        let content_repo = ContentRepositoryImpl::new(journal.clone());
+        let engine_repo = SearchEngineRepositoryImpl::new(&content_repo).await?;
+
+        let parser_repo = HtmlParserRepositoryImpl::new(journal.clone());
         let member_repo = MemberRepositoryImpl::new()?;
+        let media_repo = MediaRepositoryImpl::new();
+        let revision_repo = RevisionRepositoryImpl::new(journal.clone());
+        let page_repo = PageRepositoryImpl::new(journal.clone());
+        let unit_of_work_repo = UnitOfWorkRepositoryImpl::new(journal, engine_repo.writer_handle());
 
         Ok(Self {
             engine_repo,
             parser_repo,
             content_repo,
             member_repo,
+            media_repo,
+            revision_repo,
+            unit_of_work_repo,
+            page_repo,
         })
     }
 }
 
 impl Repositories for RepositoriesImpl {
-    fn engine<'s>(&'s self) -> &'s dyn SearchEngineRepository {
+    fn engine(&self) -> &dyn SearchEngineRepository {
         &self.engine_repo
     }
 
-    fn parser<'s>(&'s self) -> &'s dyn HtmlParserRepository {
+    fn parser(&self) -> &dyn HtmlParserRepository {
         &self.parser_repo
     }
 
-    fn content<'s>(&'s self) -> &'s dyn ContentRepository {
+    fn content(&self) -> &dyn ContentRepository {
         &self.content_repo
     }
 
-    fn member<'s>(&'s self) -> &'s dyn MemberRepository {
+    fn member(&self) -> &dyn MemberRepository {
         &self.member_repo
     }
+
+    fn media(&self) -> &dyn MediaRepository {
+        &self.media_repo
+    }
+
+    fn revision(&self) -> &dyn RevisionRepository {
+        &self.revision_repo
+    }
+
+    fn unit_of_work(&self) -> &dyn UnitOfWorkRepository {
+        &self.unit_of_work_repo
+    }
+
+    fn page(&self) -> &dyn PageRepository {
+        &self.page_repo
+    }
 }
@@ -1,25 +1,51 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
+use crate::repository::journal::FileJournal;
 use common::types::BoxError;
 use config::CONFIG;
 use domain::{
-    model::content::{ContentEntity, FrontMatterEntity},
+    model::content::{ContentEntity, FrontMatterEntity, slugify},
     repository::content::ContentRepository,
 };
 
 #[allow(dead_code)]
 pub struct ContentRepositoryImpl {
     output_path: PathBuf,
+    journal: Arc<FileJournal>,
 }
 
 impl ContentRepositoryImpl {
-    pub fn new() -> Self {
+    pub(crate) fn new(journal: Arc<FileJournal>) -> Self {
         Self {
             output_path: PathBuf::from(CONFIG.content.markdown_dir.clone()),
+            journal,
         }
     }
+
+    async fn parse(file_path: &Path) -> Result<ContentEntity, BoxError> {
+        let id = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| BoxError::from(format!("Invalid file name: {}", file_path.display())))?;
+
+        let content = tokio::fs::read_to_string(file_path).await?;
+        let (matter_yaml, body) = split_front_matter(&content, file_path)?;
+
+        let mut matter: FrontMatterEntity = serde_yaml::from_str(matter_yaml)?;
+        // 直接編集された slug も API から保存する場合と同じく URL に使える形にする
+        matter.slug = matter.slug.as_deref().and_then(slugify);
+
+        Ok(ContentEntity {
+            id: id.to_string(),
+            matter,
+            body: body.to_string(),
+        })
+    }
 }
 
 #[allow(unused_variables)]
@@ -31,14 +57,13 @@ impl ContentRepository for ContentRepositoryImpl {
         let dir = self
             .output_path
             .join(entity.matter.date.format("%Y%m").to_string());
-        tokio::fs::create_dir_all(&dir).await?;
 
-        let front_matter_yaml = serde_yaml::to_string(&entity.matter)?;
-
-        let file_content = format!("---\n{}\n---\n{}", front_matter_yaml.trim(), entity.body);
+        let file_content = join_front_matter(&entity.matter, &entity.body)?;
 
         let file_path = dir.join(format!("{}.md", entity.id.clone()));
-        tokio::fs::write(&file_path, file_content).await?;
+        self.journal
+            .write(&file_path, file_content.as_bytes())
+            .await?;
 
         Ok(ContentEntity {
             id: entity.id.clone(),
@@ -61,36 +86,11 @@ impl ContentRepository for ContentRepositoryImpl {
             return Ok(None);
         }
 
-        let content = tokio::fs::read_to_string(&file_path).await?;
-
-        if !content.starts_with("---\n") {
-            return Err(BoxError::from(format!(
-                "Front matter missing in file: {}",
-                file_path.display()
-            )));
-        }
-
-        let fm_end = content.find("\n---\n").ok_or_else(|| {
-            BoxError::from(format!(
-                "Front matter not closed properly in file: {}",
-                file_path.display()
-            ))
-        })?;
-
-        let matter_yaml = &content[4..fm_end];
-        let body = &content[fm_end + 5..];
-
-        let matter: FrontMatterEntity = serde_yaml::from_str(matter_yaml)?;
-
-        Ok(Some(ContentEntity {
-            id: id.to_string(),
-            matter,
-            body: body.to_string(),
-        }))
+        Ok(Some(Self::parse(&file_path).await?))
     }
 
     async fn remove(&self, id: &str) -> Result<u64, BoxError> {
-        let target_name = format!("{}.html", id);
+        let target_name = format!("{}.md", id);
         let mut deleted = 0u64;
 
         let mut stack = vec![self.output_path.clone()];
@@ -102,20 +102,104 @@ impl ContentRepository for ContentRepositoryImpl {
                 let path = entry.path();
                 if path.is_dir() {
                     stack.push(path);
-                } else if path.is_file() {
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        if name == target_name {
-                            match tokio::fs::remove_file(&path).await {
-                                Ok(_) => deleted += 1,
-                                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
-                                Err(e) => return Err(Box::new(e)),
-                            }
-                        }
-                    }
+                } else if path.is_file()
+                    && let Some(name) = path.file_name().and_then(|n| n.to_str())
+                    && name == target_name
+                    && self.journal.remove(&path).await?
+                {
+                    deleted += 1;
                 }
             }
         }
 
         Ok(deleted)
     }
+
+    async fn list(&self) -> Result<Vec<String>, BoxError> {
+        let mut paths = Vec::new();
+
+        let mut stack = vec![self.output_path.clone()];
+
+        while let Some(dir) = stack.pop() {
+            let mut rd = tokio::fs::read_dir(&dir).await?;
+            while let Some(entry) = rd.next_entry().await? {
+                let path = entry.path();
+                if path.is_dir() {
+                    stack.push(path);
+                } else if path.is_file() && path.extension().is_some_and(|ext| ext == "md") {
+                    paths.push(path.display().to_string());
+                }
+            }
+        }
+
+        paths.sort();
+        Ok(paths)
+    }
+
+    async fn load(&self, path: &str) -> Result<Option<ContentEntity>, BoxError> {
+        let file_path = PathBuf::from(path);
+        let entity = Self::parse(&file_path).await?;
+
+        // find() で引けない配置(日付ディレクトリ不一致)のファイルは対象外
+        let expected = self
+            .output_path
+            .join(entity.matter.date.format("%Y%m").to_string())
+            .join(format!("{}.md", entity.id));
+        if expected != file_path {
+            tracing::debug!(
+                "skip misplaced file: {} (expected {})",
+                file_path.display(),
+                expected.display()
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(entity))
+    }
+
+    // list() で返したパスのファイルだけを削除する (同じ id の古い複製の削除用)
+    async fn remove_path(&self, path: &str) -> Result<bool, BoxError> {
+        let file_path = PathBuf::from(path);
+        if !file_path.starts_with(&self.output_path)
+            || file_path.extension().is_none_or(|ext| ext != "md")
+        {
+            return Err(format!("not a markdown file: {}", path).into());
+        }
+        self.journal.remove(&file_path).await
+    }
+
+    async fn exists_path(&self, path: &str) -> Result<bool, BoxError> {
+        Ok(tokio::fs::try_exists(path).await?)
+    }
+}
+
+// Front Matter (YAML) と本文に分ける
+pub(crate) fn split_front_matter<'a>(
+    content: &'a str,
+    file_path: &Path,
+) -> Result<(&'a str, &'a str), BoxError> {
+    if !content.starts_with("---\n") {
+        return Err(BoxError::from(format!(
+            "Front matter missing in file: {}",
+            file_path.display()
+        )));
+    }
+
+    let fm_end = content.find("\n---\n").ok_or_else(|| {
+        BoxError::from(format!(
+            "Front matter not closed properly in file: {}",
+            file_path.display()
+        ))
+    })?;
+
+    Ok((&content[4..fm_end], &content[fm_end + 5..]))
+}
+
+// Front Matter 付きの Markdown ファイルの内容
+pub(crate) fn join_front_matter<T: serde::Serialize>(
+    matter: &T,
+    body: &str,
+) -> Result<String, BoxError> {
+    let front_matter_yaml = serde_yaml::to_string(matter)?;
+    Ok(format!("---\n{}\n---\n{}", front_matter_yaml.trim(), body))
 }
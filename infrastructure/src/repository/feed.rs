@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+
+pub(crate) struct FeedChannel {
+    pub title: String,
+    pub description: String,
+    pub site_url: String,
+    pub rss_url: String,
+    pub atom_url: String,
+}
+
+pub(crate) struct FeedItem {
+    pub title: String,
+    pub url: String,
+    pub description: Option<String>,
+    pub date: DateTime<Utc>,
+    pub categories: Vec<String>,
+    pub html: String,
+}
+
+pub(crate) fn rss(channel: &FeedChannel, items: &[FeedItem]) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\">\n");
+    xml.push_str("<channel>\n");
+    xml.push_str(&format!("<title>{}</title>\n", escape(&channel.title)));
+    xml.push_str(&format!("<link>{}/</link>\n", escape(&channel.site_url)));
+    xml.push_str(&format!(
+        "<description>{}</description>\n",
+        escape(&channel.description)
+    ));
+    xml.push_str(&format!(
+        "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        escape(&channel.rss_url)
+    ));
+    if let Some(latest) = items.iter().map(|item| item.date).max() {
+        xml.push_str(&format!(
+            "<lastBuildDate>{}</lastBuildDate>\n",
+            latest.to_rfc2822()
+        ));
+    }
+
+    for item in items {
+        xml.push_str("<item>\n");
+        xml.push_str(&format!("<title>{}</title>\n", escape(&item.title)));
+        xml.push_str(&format!("<link>{}</link>\n", escape(&item.url)));
+        xml.push_str(&format!(
+            "<guid isPermaLink=\"true\">{}</guid>\n",
+            escape(&item.url)
+        ));
+        xml.push_str(&format!("<pubDate>{}</pubDate>\n", item.date.to_rfc2822()));
+        if let Some(description) = &item.description {
+            xml.push_str(&format!(
+                "<description>{}</description>\n",
+                escape(description)
+            ));
+        }
+        for category in &item.categories {
+            xml.push_str(&format!("<category>{}</category>\n", escape(category)));
+        }
+        xml.push_str(&format!(
+            "<content:encoded>{}</content:encoded>\n",
+            escape(&item.html)
+        ));
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n");
+    xml.push_str("</rss>\n");
+    xml
+}
+
+pub(crate) fn atom(channel: &FeedChannel, items: &[FeedItem]) -> String {
+    // updated は必須なので記事が無い場合は現在時刻
+    let updated = items
+        .iter()
+        .map(|item| item.date)
+        .max()
+        .unwrap_or_else(Utc::now);
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("<title>{}</title>\n", escape(&channel.title)));
+    if !channel.description.is_empty() {
+        xml.push_str(&format!(
+            "<subtitle>{}</subtitle>\n",
+            escape(&channel.description)
+        ));
+    }
+    xml.push_str(&format!("<id>{}/</id>\n", escape(&channel.site_url)));
+    xml.push_str(&format!(
+        "<link href=\"{}/\"/>\n",
+        escape(&channel.site_url)
+    ));
+    xml.push_str(&format!(
+        "<link href=\"{}\" rel=\"self\" type=\"application/atom+xml\"/>\n",
+        escape(&channel.atom_url)
+    ));
+    xml.push_str(&format!("<updated>{}</updated>\n", updated.to_rfc3339()));
+    xml.push_str(&format!(
+        "<author><name>{}</name></author>\n",
+        escape(&channel.title)
+    ));
+
+    for item in items {
+        xml.push_str("<entry>\n");
+        xml.push_str(&format!("<title>{}</title>\n", escape(&item.title)));
+        xml.push_str(&format!("<id>{}</id>\n", escape(&item.url)));
+        xml.push_str(&format!("<link href=\"{}\"/>\n", escape(&item.url)));
+        xml.push_str(&format!("<updated>{}</updated>\n", item.date.to_rfc3339()));
+        xml.push_str(&format!(
+            "<published>{}</published>\n",
+            item.date.to_rfc3339()
+        ));
+        if let Some(description) = &item.description {
+            xml.push_str(&format!("<summary>{}</summary>\n", escape(description)));
+        }
+        for category in &item.categories {
+            xml.push_str(&format!("<category term=\"{}\"/>\n", escape(category)));
+        }
+        xml.push_str(&format!(
+            "<content type=\"html\">{}</content>\n",
+            escape(&item.html)
+        ));
+        xml.push_str("</entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // XML 1.0 で使えない制御文字は除去
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,42 +1,444 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use pulldown_cmark::{Options, Parser, html};
-use std::path::PathBuf;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
 use tera::{Context, Tera};
+use tokio::sync::Mutex;
 
+use crate::repository::{
+    feed::{self, FeedChannel, FeedItem},
+    journal::FileJournal,
+    sitemap::{self, SitemapUrl},
+};
 use common::types::BoxError;
 use config::CONFIG;
-use domain::{model::content::ContentEntity, repository::html_parser::HtmlParserRepository};
+use domain::{
+    model::{
+        archive::ArchiveKind,
+        content::ContentEntity,
+        page::PageEntity,
+        search_engine::{SearchIndexEntry, SearchResult},
+    },
+    repository::html_parser::HtmlParserRepository,
+};
+
+#[derive(Serialize)]
+struct ListItem<'a> {
+    #[serde(flatten)]
+    content: &'a ContentEntity,
+    url: String,
+}
+
+#[derive(Serialize)]
+struct MenuItem<'a> {
+    id: &'a str,
+    title: &'a str,
+    url: String,
+    order: i32,
+}
+
+// 静的サイト用の検索インデックスの出力先 (html_dir からの相対パス)
+const SEARCH_INDEX_DIR: &str = "search";
+// 記事以外を出力するディレクトリ (html_dir 直下)
+const RESERVED_DIRS: [&str; 5] = ["list", "tags", "categories", "archives", SEARCH_INDEX_DIR];
+// 出力した記事の HTML の末尾に付ける印 (check で ssg-mng が出力したファイルだけを対象にする)
+const CONTENT_MARKER: &str = "<!-- ssg-mng:content ";
+// 記事の HTML に載せた関連記事の id (関連記事に載せている記事を出力し直すため)
+const RELATED_MARKER: &str = "<!-- ssg-mng:related ";
+// リダイレクト用 HTML の末尾に付ける転送先の記事の id (記事の削除時にまとめて削除するため)
+const REDIRECT_MARKER: &str = "<!-- ssg-mng:redirect ";
+
+// 検索インデックスの記事 (空の項目は出力しない)
+#[derive(Serialize)]
+struct SearchIndexItem<'a> {
+    id: &'a str,
+    url: String,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    date: DateTime<Utc>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    tags: &'a [String],
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    categories: &'a [String],
+    terms: &'a [String],
+}
+
+#[derive(Serialize)]
+struct SearchIndexShard {
+    file: String,
+    count: usize,
+}
+
+// 検索インデックスの目次 (ブラウザ側はこれを読んでから各分割ファイルを取得する)
+#[derive(Serialize)]
+struct SearchIndexManifest {
+    generated: DateTime<Utc>,
+    total: usize,
+    shards: Vec<SearchIndexShard>,
+}
+
+// 前回出力した分割ファイル (内容が同じで、取り消し等でファイルが変わっていなければ出力しない)
+#[derive(PartialEq)]
+struct WrittenShard {
+    digest: Vec<u8>,
+    len: u64,
+    modified: SystemTime,
+}
 
 #[allow(dead_code)]
 pub struct HtmlParserRepositoryImpl {
     template_path: PathBuf,
     output_path: PathBuf,
+    journal: Arc<FileJournal>,
+    // 関連記事の id → その記事を関連記事に載せている記事の id (最初に HTML から読み込む)
+    // 取り消された出力の分も残るが、多めに出力し直すだけのため削除しない
+    related_to: Mutex<Option<HashMap<String, BTreeSet<String>>>>,
+    search_shards: Mutex<HashMap<String, WrittenShard>>,
 }
 
 impl HtmlParserRepositoryImpl {
-    pub fn new() -> Self {
+    pub(crate) fn new(journal: Arc<FileJournal>) -> Self {
         Self {
             template_path: PathBuf::from(CONFIG.content.template_dir.clone()),
             output_path: PathBuf::from(CONFIG.content.html_dir.clone()),
+            journal,
+            related_to: Mutex::new(None),
+            search_shards: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn markdown_to_html(markdown: &str) -> String {
+        let parser = Parser::new_ext(markdown, Options::all());
+        let mut html_body = String::new();
+        html::push_html(&mut html_body, parser);
+        html_body
+    }
+
+    // permalink の設定から記事の URL を組み立てる ({slug} は未設定の場合 id)
+    fn content_url(entity: &ContentEntity) -> String {
+        let date = entity.matter.date;
+        CONFIG
+            .content
+            .permalink
+            .replace("{year}", &date.format("%Y").to_string())
+            .replace("{month}", &date.format("%m").to_string())
+            .replace("{day}", &date.format("%d").to_string())
+            .replace("{id}", &entity.id)
+            .replace(
+                "{slug}",
+                entity.matter.slug.as_deref().unwrap_or(&entity.id),
+            )
+    }
+
+    // URL が / で終わる場合はそのディレクトリの index.html に出力する
+    // slug 等で html_dir の外を指す場合はエラー
+    fn content_path(&self, entity: &ContentEntity) -> Result<PathBuf, BoxError> {
+        self.file_path(&Self::content_file_of(entity))
+    }
+
+    // html_dir からの相対パス
+    fn content_file_of(entity: &ContentEntity) -> String {
+        Self::url_to_file(&Self::content_url(entity))
+    }
+
+    fn url_to_file(url: &str) -> String {
+        let file = url.trim_start_matches('/');
+        if url.ends_with('/') {
+            format!("{}index.html", file)
+        } else {
+            file.to_string()
+        }
+    }
+
+    // 記事の印のある HTML のパスと内容 (パス順)
+    async fn content_htmls(&self) -> Result<Vec<(String, String)>, BoxError> {
+        self.marked_htmls(CONTENT_MARKER).await
+    }
+
+    // 出力済みの HTML の関連記事の印から逆引きの表を作る
+    async fn load_related_to(&self) -> Result<HashMap<String, BTreeSet<String>>, BoxError> {
+        let mut related_to = HashMap::<String, BTreeSet<String>>::new();
+        for (_, html) in self.content_htmls().await? {
+            let Some(id) = Self::marker_value(&html, CONTENT_MARKER) else {
+                continue;
+            };
+            let related = Self::marker_value(&html, RELATED_MARKER).unwrap_or_default();
+            for related_id in related.split(' ').filter(|v| !v.is_empty()) {
+                related_to
+                    .entry(related_id.to_string())
+                    .or_default()
+                    .insert(id.to_string());
+            }
+        }
+        Ok(related_to)
+    }
+
+    // marker のある HTML のパスと内容 (パス順)
+    async fn marked_htmls(&self, marker: &str) -> Result<Vec<(String, String)>, BoxError> {
+        let media_dir = CONFIG.media.dir.trim_matches('/').to_string();
+
+        let mut files = Vec::new();
+        if !self.output_path.is_dir() {
+            return Ok(files);
+        }
+        let mut stack = vec![self.output_path.clone()];
+        while let Some(dir) = stack.pop() {
+            let mut rd = tokio::fs::read_dir(&dir).await?;
+            while let Some(entry) = rd.next_entry().await? {
+                let path = entry.path();
+                let Ok(relative) = path.strip_prefix(&self.output_path) else {
+                    continue;
+                };
+                let file = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+
+                if path.is_dir() {
+                    if !RESERVED_DIRS.contains(&file.as_str()) && file != media_dir {
+                        stack.push(path);
+                    }
+                    continue;
+                }
+                if !file.ends_with(".html") {
+                    continue;
+                }
+                let html = tokio::fs::read_to_string(&path).await.unwrap_or_default();
+                if html.contains(marker) {
+                    files.push((file, html));
+                }
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+
+    // 末尾に付けた印の値 (印から " -->" まで)
+    fn marker_value<'a>(html: &'a str, marker: &str) -> Option<&'a str> {
+        let start = html.rfind(marker)? + marker.len();
+        let end = html[start..].find(" -->")?;
+        Some(&html[start..start + end])
+    }
+
+    // html_dir からの相対パスを html_dir の外に出ないものに限って解決する
+    fn file_path(&self, file: &str) -> Result<PathBuf, BoxError> {
+        let relative = Path::new(file);
+        if !relative
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(format!("invalid html file: {}", file).into());
         }
+        Ok(self.output_path.join(relative))
+    }
+
+    // 固定ページは日付を含まない /{id}.html
+    fn page_url(entity: &PageEntity) -> String {
+        format!("/{}.html", entity.id)
+    }
+
+    fn page_path(&self, entity: &PageEntity) -> PathBuf {
+        self.output_path
+            .join(Self::page_url(entity).trim_start_matches('/'))
+    }
+
+    fn redirect_html(url: &str, id: &str) -> String {
+        let url = feed::escape(&sitemap::encode_path(url));
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Redirecting...</title>\n<link rel=\"canonical\" href=\"{url}\">\n<meta http-equiv=\"refresh\" content=\"0; url={url}\">\n</head>\n<body>\n<a href=\"{url}\">{url}</a>\n</body>\n</html>\n{REDIRECT_MARKER}{id} -->\n"
+        )
+    }
+
+    fn list_context(result: &SearchResult) -> Context {
+        let contents = result
+            .contents
+            .iter()
+            .map(|content| ListItem {
+                content,
+                url: Self::content_url(content),
+            })
+            .collect::<Vec<_>>();
+
+        let mut context = Context::new();
+        context.insert("contents", &contents);
+        context.insert("page", &result.page);
+        context.insert("per_page", &result.per_page);
+        context.insert("max_page", &result.max_page);
+        context
+    }
+
+    // タグ・カテゴリー名をディレクトリ名として使える形にする
+    fn dir_name(name: &str) -> String {
+        let mut dir_name = name
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | '?' | '#' | '%' | ':' | '*' | '"' | '<' | '>' | '|' => '_',
+                c if c.is_control() => '_',
+                c => c,
+            })
+            .collect::<String>();
+        if dir_name.starts_with('.') {
+            dir_name.insert(0, '_');
+        }
+        dir_name
+    }
+
+    fn archive_base(kind: &ArchiveKind) -> String {
+        match kind {
+            ArchiveKind::List => "list".to_string(),
+            ArchiveKind::Tag(tag) => format!("tags/{}", Self::dir_name(tag)),
+            ArchiveKind::Category(category) => format!("categories/{}", Self::dir_name(category)),
+            ArchiveKind::Month(year, month) => format!("archives/{:04}/{:02}", year, month),
+        }
+    }
+
+    fn list_url(base: &str, page: usize) -> String {
+        if page <= 1 {
+            format!("/{}/", base)
+        } else {
+            format!("/{}/{}/", base, page)
+        }
+    }
+
+    fn list_path(&self, base: &str, page: usize) -> PathBuf {
+        let dir = self.output_path.join(base);
+        if page <= 1 {
+            dir.join("index.html")
+        } else {
+            dir.join(page.to_string()).join("index.html")
+        }
+    }
+
+    // テンプレートが存在しない場合は None を返す
+    async fn render(&self, template: &str, context: Context) -> Result<Option<String>, BoxError> {
+        let template_path = self.template_path.clone();
+        let template = template.to_string();
+
+        tokio::task::spawn_blocking(move || -> Result<Option<String>, BoxError> {
+            let tera = Tera::new(&format!("{}/**/*.html", template_path.display()))?;
+            if !tera.get_template_names().any(|name| name == template) {
+                tracing::debug!("template not found, skip rendering: {}", template);
+                return Ok(None);
+            }
+            Ok(Some(tera.render(&template, &context)?))
+        })
+        .await?
+    }
+
+    async fn write(&self, path: &Path, rendered: &str) -> Result<(), BoxError> {
+        self.journal.write(path, rendered.as_bytes()).await
+    }
+
+    fn search_shard_name(index: usize) -> String {
+        format!("shard-{}.json", index)
+    }
+
+    // 前回と内容が異なる分割ファイルだけを出力する
+    async fn write_search_shard(
+        &self,
+        path: &Path,
+        file: &str,
+        body: &str,
+    ) -> Result<(), BoxError> {
+        let digest = Sha256::digest(body.as_bytes()).to_vec();
+        let mut written = self.search_shards.lock().await;
+        if let Some(shard) = written.get(file)
+            && shard.digest == digest
+            && let Ok(metadata) = tokio::fs::metadata(path).await
+            && metadata.len() == shard.len
+            && metadata.modified().ok() == Some(shard.modified)
+        {
+            return Ok(());
+        }
+
+        self.write(path, body).await?;
+        let metadata = tokio::fs::metadata(path).await?;
+        written.insert(
+            file.to_string(),
+            WrittenShard {
+                digest,
+                len: metadata.len(),
+                modified: metadata.modified()?,
+            },
+        );
+        Ok(())
+    }
+
+    // 今回出力しなかった分割ファイル (記事が減った場合の shard-N.json) を削除する
+    async fn prune_search_shards(&self, shard_count: usize) -> Result<(), BoxError> {
+        let dir = self.output_path.join(SEARCH_INDEX_DIR);
+        if !dir.is_dir() {
+            return Ok(());
+        }
+
+        let mut rd = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = rd.next_entry().await? {
+            let path = entry.path();
+            let index = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix("shard-"))
+                .and_then(|n| n.strip_suffix(".json"))
+                .and_then(|n| n.parse::<usize>().ok());
+            let Some(index) = index else {
+                continue;
+            };
+            if path.is_file() && (index == 0 || index > shard_count) {
+                self.journal.remove(&path).await?;
+                self.search_shards
+                    .lock()
+                    .await
+                    .remove(&Self::search_shard_name(index));
+            }
+        }
+
+        Ok(())
     }
 }
 
 #[allow(unused_variables)]
 #[async_trait]
 impl HtmlParserRepository for HtmlParserRepositoryImpl {
-    async fn create(&self, entity: &ContentEntity) -> Result<String, BoxError> {
+    async fn create(
+        &self,
+        entity: &ContentEntity,
+        related: &[ContentEntity],
+    ) -> Result<String, BoxError> {
         let mut entity_ref = entity.clone();
         let template_path = self.template_path.clone();
         let output_path = self.output_path.clone();
 
+        let related_ids = related
+            .iter()
+            .map(|content| content.id.clone())
+            .collect::<Vec<_>>();
+        let related_marker = related_ids.join(" ");
+        // 関連記事は一覧ページと同じく url を付けて渡す
+        let related = related
+            .iter()
+            .map(|content| ListItem {
+                content,
+                url: Self::content_url(content),
+            })
+            .collect::<Vec<_>>();
+        let mut context = Context::new();
+        context.insert("related", &related);
+
         // spawn_blocking で Markdown → HTML + Tera レンダリング
         let (rendered, html_body) =
             tokio::task::spawn_blocking(move || -> Result<(String, String), BoxError> {
                 // 1. Markdown → HTML
-                let parser = Parser::new_ext(&entity_ref.body, Options::all());
-                let mut html_body = String::new();
-                html::push_html(&mut html_body, parser);
+                let html_body = Self::markdown_to_html(&entity_ref.body);
 
                 // body を HTML に置き換え
                 entity_ref.body = html_body.clone();
@@ -45,57 +447,467 @@ impl HtmlParserRepository for HtmlParserRepositoryImpl {
                 let tera = Tera::new(&format!("{}/**/*.html", template_path.display()))?;
 
                 // 3. Context 作成
-                let mut context = Context::new();
                 context.insert("content", &entity_ref);
 
                 // 4. テンプレート適用
-                let rendered = tera.render(&CONFIG.content.template_content, &context)?;
+                let mut rendered = tera.render(&CONFIG.content.template_content, &context)?;
+                if !related_marker.is_empty() {
+                    rendered.push_str(&format!("\n{}{} -->", RELATED_MARKER, related_marker));
+                }
+                rendered.push_str(&format!("\n{}{} -->\n", CONTENT_MARKER, entity_ref.id));
 
                 Ok((rendered, html_body))
             })
             .await??;
 
-        if !entity.matter.draft {
-            // 5. ファイル保存先ディレクトリを作成
-            let dir = self
-                .output_path
-                .join(entity.matter.date.format("%Y%m").to_string());
-            tokio::fs::create_dir_all(&dir).await?;
+        // 下書きと公開日時前の予約投稿はファイルを出力しない
+        if !entity.matter.draft && entity.matter.date <= Utc::now() {
+            // 5. permalink の出力先に HTML ファイル保存
+            self.write(&self.content_path(entity)?, &rendered).await?;
 
-            // 6. tokio::fs で HTML ファイル保存
-            let output_file = dir.join(format!("{}.html", entity.id));
-            tokio::fs::write(&output_file, &rendered).await?;
+            // 読み込み済みの場合のみ追加する (未読み込みなら次に読み込む際に含まれる)
+            if let Some(related_to) = self.related_to.lock().await.as_mut() {
+                for related_id in &related_ids {
+                    related_to
+                        .entry(related_id.clone())
+                        .or_default()
+                        .insert(entity.id.clone());
+                }
+            }
         }
 
         Ok(html_body)
     }
 
-    async fn remove(&self, id: &str) -> Result<u64, BoxError> {
-        let target_name = format!("{}.html", id);
+    async fn exists(&self, entity: &ContentEntity) -> Result<bool, BoxError> {
+        Ok(tokio::fs::try_exists(self.content_path(entity)?).await?)
+    }
+
+    fn content_file(&self, entity: &ContentEntity) -> String {
+        Self::content_file_of(entity)
+    }
+
+    // 記事の印のある HTML の一覧 (html_dir からの相対パス)
+    // 手書きの HTML・固定ページ・リダイレクト用 HTML 等、印の無いファイルは含めない
+    async fn list_content_files(&self) -> Result<Vec<String>, BoxError> {
+        let files = self.content_htmls().await?;
+        Ok(files.into_iter().map(|(file, _)| file).collect())
+    }
+
+    // ids のいずれかを関連記事に載せている記事の id
+    // 逆引きの表から引くため、HTML を読むのは最初の 1 回だけ
+    async fn list_related_to(&self, ids: &[String]) -> Result<Vec<String>, BoxError> {
+        let mut guard = self.related_to.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.load_related_to().await?);
+        }
+        let related_to = guard.as_ref().unwrap();
+
+        let found = ids
+            .iter()
+            .filter_map(|id| related_to.get(id))
+            .flatten()
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        Ok(found.into_iter().collect())
+    }
+
+    async fn file_exists(&self, file: &str) -> Result<bool, BoxError> {
+        let path = self.file_path(file)?;
+        Ok(tokio::fs::try_exists(path).await?)
+    }
+
+    async fn remove_file(&self, file: &str) -> Result<bool, BoxError> {
+        let path = self.file_path(file)?;
+        self.journal.remove(&path).await
+    }
+
+    async fn remove(&self, entity: &ContentEntity) -> Result<u64, BoxError> {
+        let removed = self.journal.remove(&self.content_path(entity)?).await?;
+        Ok(removed as u64)
+    }
+
+    // 記事の URL が変わった場合に旧 URL へリダイレクト用の HTML を出力する
+    async fn create_redirect(
+        &self,
+        from: &ContentEntity,
+        to: &ContentEntity,
+    ) -> Result<(), BoxError> {
+        let (from_url, to_url) = (Self::content_url(from), Self::content_url(to));
+        if from_url == to_url {
+            return Ok(());
+        }
+
+        tracing::debug!("create redirect: {} -> {}", from_url, to_url);
+        self.write(
+            &self.content_path(from)?,
+            &Self::redirect_html(&to_url, &to.id),
+        )
+        .await
+    }
+
+    // 記事の旧 URL に出力したリダイレクト用 HTML をすべて削除する
+    async fn remove_redirects(&self, id: &str) -> Result<u64, BoxError> {
+        let mut removed = 0;
+        for (file, html) in self.marked_htmls(REDIRECT_MARKER).await? {
+            if Self::marker_value(&html, REDIRECT_MARKER) == Some(id) {
+                tracing::debug!("remove redirect: {}", file);
+                if self.journal.remove(&self.output_path.join(&file)).await? {
+                    removed += 1;
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn create_index(&self, result: &SearchResult) -> Result<(), BoxError> {
+        let context = Self::list_context(result);
+        if let Some(rendered) = self.render(&CONFIG.content.template_index, context).await? {
+            self.write(&self.output_path.join("index.html"), &rendered)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn create_list(&self, kind: &ArchiveKind, result: &SearchResult) -> Result<(), BoxError> {
+        let base = Self::archive_base(kind);
+        let (kind_name, name) = match kind {
+            ArchiveKind::List => ("list", None),
+            ArchiveKind::Tag(tag) => ("tags", Some(tag.clone())),
+            ArchiveKind::Category(category) => ("categories", Some(category.clone())),
+            ArchiveKind::Month(year, month) => {
+                ("archives", Some(format!("{:04}-{:02}", year, month)))
+            }
+        };
+
+        let mut context = Self::list_context(result);
+        context.insert("base_url", &format!("/{}/", base));
+        context.insert("kind", kind_name);
+        context.insert("name", &name);
+        if let Some(rendered) = self.render(&CONFIG.content.template_list, context).await? {
+            self.write(&self.list_path(&base, result.page), &rendered)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn prune_list(&self, kind: &ArchiveKind, max_page: usize) -> Result<u64, BoxError> {
+        let dir = self.output_path.join(Self::archive_base(kind));
+        if !dir.is_dir() {
+            return Ok(0);
+        }
+
         let mut deleted = 0u64;
+        let mut rd = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = rd.next_entry().await? {
+            let path = entry.path();
+            let page = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.parse::<usize>().ok());
+            let Some(page) = page else {
+                continue;
+            };
+            if path.is_dir() && page > max_page.max(1) && self.journal.remove(&path).await? {
+                deleted += 1;
+            }
+        }
 
-        let mut stack = vec![self.output_path.clone()];
+        Ok(deleted)
+    }
+
+    async fn prune_archives(&self, keep: &[ArchiveKind]) -> Result<u64, BoxError> {
+        let keep = keep.iter().map(Self::archive_base).collect::<HashSet<_>>();
+        let mut deleted = 0u64;
+
+        for family in ["tags", "categories"] {
+            let dir = self.output_path.join(family);
+            if !dir.is_dir() {
+                continue;
+            }
 
-        while let Some(dir) = stack.pop() {
             let mut rd = tokio::fs::read_dir(&dir).await?;
             while let Some(entry) = rd.next_entry().await? {
                 let path = entry.path();
-                if path.is_dir() {
-                    stack.push(path);
-                } else if path.is_file() {
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        if name == target_name {
-                            match tokio::fs::remove_file(&path).await {
-                                Ok(_) => deleted += 1,
-                                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
-                                Err(e) => return Err(Box::new(e)),
-                            }
-                        }
+                // . で始まるものは作業中の退避先
+                let Some(name) = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .filter(|n| !n.starts_with('.'))
+                else {
+                    continue;
+                };
+                if path.is_dir()
+                    && !keep.contains(&format!("{}/{}", family, name))
+                    && self.journal.remove(&path).await?
+                {
+                    deleted += 1;
+                }
+            }
+        }
+
+        // 年月別一覧は archives/{年}/{月} の2階層
+        let dir = self.output_path.join("archives");
+        if dir.is_dir() {
+            let mut years = tokio::fs::read_dir(&dir).await?;
+            while let Some(year) = years.next_entry().await? {
+                let year_path = year.path();
+                let Some(year_name) = year_path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .filter(|n| !n.starts_with('.'))
+                else {
+                    continue;
+                };
+                if !year_path.is_dir() {
+                    continue;
+                }
+
+                let mut remain = 0usize;
+                let mut months = tokio::fs::read_dir(&year_path).await?;
+                while let Some(month) = months.next_entry().await? {
+                    let month_path = month.path();
+                    let Some(month_name) = month_path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .filter(|n| !n.starts_with('.'))
+                    else {
+                        continue;
+                    };
+                    if month_path.is_dir()
+                        && !keep.contains(&format!("archives/{}/{}", year_name, month_name))
+                    {
+                        self.journal.remove(&month_path).await?;
+                        deleted += 1;
+                    } else {
+                        remain += 1;
                     }
                 }
+                if remain == 0 {
+                    self.journal.remove(&year_path).await?;
+                }
             }
         }
 
         Ok(deleted)
     }
+
+    async fn create_recent(&self, result: &SearchResult) -> Result<(), BoxError> {
+        let context = Self::list_context(result);
+        if let Some(rendered) = self
+            .render(&CONFIG.content.template_recent, context)
+            .await?
+        {
+            self.write(&self.output_path.join("recent.html"), &rendered)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn create_feeds(&self, contents: &[ContentEntity]) -> Result<(), BoxError> {
+        let site_url = CONFIG.content.site_url.clone();
+        let channel = FeedChannel {
+            title: CONFIG.content.site_title.clone(),
+            description: CONFIG.content.site_description.clone(),
+            rss_url: format!("{}/feed.xml", site_url),
+            atom_url: format!("{}/atom.xml", site_url),
+            site_url: site_url.clone(),
+        };
+        let items = contents
+            .iter()
+            .map(|content| FeedItem {
+                title: content.matter.title.clone(),
+                url: format!(
+                    "{}{}",
+                    site_url,
+                    sitemap::encode_path(&Self::content_url(content))
+                ),
+                description: content.matter.description.clone(),
+                date: content.matter.date,
+                categories: content.matter.categories.clone(),
+                html: Self::markdown_to_html(&content.body),
+            })
+            .collect::<Vec<_>>();
+
+        self.write(
+            &self.output_path.join("feed.xml"),
+            &feed::rss(&channel, &items),
+        )
+        .await?;
+        self.write(
+            &self.output_path.join("atom.xml"),
+            &feed::atom(&channel, &items),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_sitemap(
+        &self,
+        contents: &[ContentEntity],
+        pages: &[(ArchiveKind, usize)],
+    ) -> Result<(), BoxError> {
+        let site_url = &CONFIG.content.site_url;
+        let mut urls = vec![SitemapUrl {
+            loc: format!("{}/", site_url),
+            lastmod: contents.iter().map(|content| content.matter.date).max(),
+        }];
+
+        for (kind, max_page) in pages {
+            let base = Self::archive_base(kind);
+            for page in 1..=(*max_page).max(1) {
+                urls.push(SitemapUrl {
+                    loc: format!(
+                        "{}{}",
+                        site_url,
+                        sitemap::encode_path(&Self::list_url(&base, page))
+                    ),
+                    lastmod: None,
+                });
+            }
+        }
+
+        for content in contents.iter().filter(|content| !content.matter.draft) {
+            urls.push(SitemapUrl {
+                loc: format!(
+                    "{}{}",
+                    site_url,
+                    sitemap::encode_path(&Self::content_url(content))
+                ),
+                lastmod: Some(content.matter.date),
+            });
+        }
+
+        self.write(
+            &self.output_path.join("sitemap.xml"),
+            &sitemap::sitemap(&urls),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_robots(&self) -> Result<(), BoxError> {
+        let robots = CONFIG
+            .content
+            .robots_txt
+            .clone()
+            .unwrap_or_else(|| sitemap::robots(&CONFIG.content.site_url));
+        self.write(&self.output_path.join("robots.txt"), &robots)
+            .await?;
+        Ok(())
+    }
+
+    // search/index.json と、export_shard_size ごとに分割した search/shard-N.json を出力する
+    // 記事が 1 件も無い場合も空の目次を出力する
+    async fn create_search_index(&self, entries: &[SearchIndexEntry]) -> Result<(), BoxError> {
+        let dir = self.output_path.join(SEARCH_INDEX_DIR);
+        let shard_size = CONFIG.search.export_shard_size;
+
+        // 新しい記事は末尾の分割ファイルに入るよう古い順に並べ、変更のあった分割ファイルだけを出力し直す
+        let mut entries = entries.iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| {
+            (a.content.matter.date, &a.content.id).cmp(&(b.content.matter.date, &b.content.id))
+        });
+
+        let mut shards: Vec<(String, usize)> = Vec::new();
+        let mut current = String::new();
+        let mut count = 0usize;
+        for entry in &entries {
+            let content = &entry.content;
+            let item = serde_json::to_string(&SearchIndexItem {
+                id: &content.id,
+                url: Self::content_url(content),
+                title: &content.matter.title,
+                description: content
+                    .matter
+                    .description
+                    .as_deref()
+                    .filter(|d| !d.is_empty()),
+                date: content.matter.date,
+                tags: &content.matter.tags,
+                categories: &content.matter.categories,
+                terms: &entry.terms,
+            })?;
+
+            // 1 件で上限を超える場合もその記事だけの分割ファイルにする
+            if count > 0 && current.len() + item.len() + 2 > shard_size {
+                shards.push((std::mem::take(&mut current), count));
+                count = 0;
+            }
+            current.push(if count == 0 { '[' } else { ',' });
+            current.push_str(&item);
+            count += 1;
+        }
+        if count > 0 {
+            shards.push((current, count));
+        }
+
+        let mut manifest = SearchIndexManifest {
+            generated: Utc::now(),
+            total: entries.len(),
+            shards: Vec::new(),
+        };
+        for (i, (mut body, count)) in shards.into_iter().enumerate() {
+            body.push(']');
+            let file = Self::search_shard_name(i + 1);
+            self.write_search_shard(&dir.join(&file), &file, &body)
+                .await?;
+            manifest.shards.push(SearchIndexShard { file, count });
+        }
+        self.write(&dir.join("index.json"), &serde_json::to_string(&manifest)?)
+            .await?;
+        self.prune_search_shards(manifest.shards.len()).await?;
+
+        Ok(())
+    }
+
+    // 下書きの固定ページはファイルを出力しない
+    // menu は公開中の固定ページのみテンプレートに渡す
+    fn page_file(&self, entity: &PageEntity) -> String {
+        Self::url_to_file(&Self::page_url(entity))
+    }
+
+    async fn create_page(&self, entity: &PageEntity, menu: &[PageEntity]) -> Result<(), BoxError> {
+        if entity.matter.draft {
+            return Ok(());
+        }
+
+        let menu = menu
+            .iter()
+            .filter(|page| !page.matter.draft)
+            .map(|page| MenuItem {
+                id: &page.id,
+                title: &page.matter.title,
+                url: Self::page_url(page),
+                order: page.matter.order,
+            })
+            .collect::<Vec<_>>();
+        let page = PageEntity {
+            body: Self::markdown_to_html(&entity.body),
+            ..entity.clone()
+        };
+
+        let template = entity
+            .matter
+            .template
+            .clone()
+            .unwrap_or_else(|| CONFIG.content.template_page.clone());
+        let mut context = Context::new();
+        context.insert("page", &page);
+        context.insert("url", &Self::page_url(entity));
+        context.insert("menu", &menu);
+
+        let rendered = self
+            .render(&template, context)
+            .await?
+            .ok_or_else(|| BoxError::from(format!("template not found: {}", template)))?;
+        self.write(&self.page_path(entity), &rendered).await
+    }
+
+    async fn remove_page(&self, entity: &PageEntity) -> Result<u64, BoxError> {
+        let removed = self.journal.remove(&self.page_path(entity)).await?;
+        Ok(removed as u64)
+    }
 }
@@ -18,7 +18,14 @@ enum Command {
         term: Term,
         respond: oneshot::Sender<Result<u64, BoxError>>,
     },
+    DeleteAll {
+        respond: oneshot::Sender<Result<(), BoxError>>,
+    },
     Commit {
+        payload: Option<String>,
+        respond: oneshot::Sender<Result<(), BoxError>>,
+    },
+    Rollback {
         respond: oneshot::Sender<Result<(), BoxError>>,
     },
 }
@@ -43,10 +50,41 @@ impl IndexWriterHandle {
         rx.await?
     }
 
+    // delete all documents
+    pub(crate) async fn delete_all(&self) -> Result<(), BoxError> {
+        let (tx, rx) = oneshot::channel();
+        self.sender.send(Command::DeleteAll { respond: tx }).await?;
+        rx.await?
+    }
+
     // commit
     pub(crate) async fn commit(&self) -> Result<(), BoxError> {
         let (tx, rx) = oneshot::channel();
-        self.sender.send(Command::Commit { respond: tx }).await?;
+        self.sender
+            .send(Command::Commit {
+                payload: None,
+                respond: tx,
+            })
+            .await?;
+        rx.await?
+    }
+
+    // commit with payload (障害復旧時にどの作業単位までコミット済みかを判定する)
+    pub(crate) async fn commit_with_payload(&self, payload: &str) -> Result<(), BoxError> {
+        let (tx, rx) = oneshot::channel();
+        self.sender
+            .send(Command::Commit {
+                payload: Some(payload.to_string()),
+                respond: tx,
+            })
+            .await?;
+        rx.await?
+    }
+
+    // rollback uncommitted changes
+    pub(crate) async fn rollback(&self) -> Result<(), BoxError> {
+        let (tx, rx) = oneshot::channel();
+        self.sender.send(Command::Rollback { respond: tx }).await?;
         rx.await?
     }
 }
@@ -67,21 +105,41 @@ pub(crate) fn spawn_index_writer_task(mut writer: IndexWriter) -> IndexWriterHan
                     let _ = respond.send(res);
                 }
                 Command::DeleteTerm { term, respond } => {
-                    let res = (|| -> Result<u64, BoxError> {
+                    let res = {
                         writer.delete_term(term);
                         tracing::debug!("delete term !!!");
                         Ok(0)
+                    };
+                    let _ = respond.send(res);
+                }
+                Command::DeleteAll { respond } => {
+                    let res = (|| -> Result<(), BoxError> {
+                        writer.delete_all_documents()?;
+                        tracing::debug!("delete all documents !!!");
+                        Ok(())
                     })();
                     let _ = respond.send(res);
                 }
-                Command::Commit { respond } => {
+                Command::Commit { payload, respond } => {
                     let res = (|| -> Result<(), BoxError> {
-                        writer.commit()?;
+                        let mut prepared = writer.prepare_commit()?;
+                        if let Some(payload) = payload.as_deref() {
+                            prepared.set_payload(payload);
+                        }
+                        prepared.commit()?;
                         tracing::debug!("commit !!!");
                         Ok(())
                     })();
                     let _ = respond.send(res);
                 }
+                Command::Rollback { respond } => {
+                    let res = (|| -> Result<(), BoxError> {
+                        writer.rollback()?;
+                        tracing::debug!("rollback !!!");
+                        Ok(())
+                    })();
+                    let _ = respond.send(res);
+                }
             }
         }
     });
@@ -0,0 +1,413 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use common::types::BoxError;
+
+// 作業単位の中で変更したファイルと、変更前の内容の退避先
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalEntry {
+    path: PathBuf,
+    backup: Option<PathBuf>, // None の場合は作業単位の中で新規に作成したファイル
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalState {
+    id: String,
+    entries: Vec<JournalEntry>,
+}
+
+// Markdown・HTML・リビジョンのファイル操作を記録し、まとめて確定・取り消しできるようにする
+// 書き込みは一時ファイルに出力してから rename で置き換え、変更前のファイルは同じディレクトリに退避する
+// 記録はファイルにも保存し、処理中に停止した場合は起動時に recover で復旧する
+pub(crate) struct FileJournal {
+    path: PathBuf,
+    state: Mutex<Option<JournalState>>,
+}
+
+impl FileJournal {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            state: Mutex::new(None),
+        }
+    }
+
+    pub(crate) async fn begin(&self) -> Result<String, BoxError> {
+        let mut state = self.state.lock().await;
+        if state.is_some() {
+            return Err("unit of work already in progress".into());
+        }
+
+        let journal = JournalState {
+            id: Uuid::new_v4().simple().to_string(),
+            entries: Vec::new(),
+        };
+        self.persist(&journal).await?;
+
+        let id = journal.id.clone();
+        *state = Some(journal);
+        Ok(id)
+    }
+
+    pub(crate) async fn id(&self) -> Option<String> {
+        self.state.lock().await.as_ref().map(|s| s.id.clone())
+    }
+
+    pub(crate) async fn write(&self, path: &Path, data: &[u8]) -> Result<(), BoxError> {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let temp = sibling(path, &format!("{}.tmp", Uuid::new_v4().simple()));
+        if let Err(e) = tokio::fs::write(&temp, data).await {
+            let _ = tokio::fs::remove_file(&temp).await;
+            return Err(Box::new(e));
+        }
+
+        let mut state = self.state.lock().await;
+        let recorded = match state.as_mut() {
+            Some(journal) => self.record(journal, path).await,
+            None => Ok(()),
+        };
+        if let Err(e) = recorded {
+            let _ = tokio::fs::remove_file(&temp).await;
+            return Err(e);
+        }
+
+        if let Err(e) = tokio::fs::rename(&temp, path).await {
+            let _ = tokio::fs::remove_file(&temp).await;
+            return Err(Box::new(e));
+        }
+        Ok(())
+    }
+
+    // ファイルまたはディレクトリを削除する (作業単位の中では退避するだけ)
+    pub(crate) async fn remove(&self, path: &Path) -> Result<bool, BoxError> {
+        if !tokio::fs::try_exists(path).await? {
+            return Ok(false);
+        }
+
+        let mut state = self.state.lock().await;
+        match state.as_mut() {
+            Some(journal) if !journal.entries.iter().any(|e| e.path == path) => {
+                self.record(journal, path).await?;
+            }
+            _ => remove_path(path)?,
+        }
+        Ok(true)
+    }
+
+    // 退避したファイルを削除して変更を確定する
+    pub(crate) async fn commit(&self) -> Result<(), BoxError> {
+        let Some(journal) = self.state.lock().await.take() else {
+            return Ok(());
+        };
+        roll_forward(&journal.entries);
+        self.clear().await
+    }
+
+    // 変更したファイルを削除し、退避したファイルを元に戻す
+    pub(crate) async fn rollback(&self) -> Result<(), BoxError> {
+        let Some(journal) = self.state.lock().await.take() else {
+            return Ok(());
+        };
+        roll_back(&journal.entries)?;
+        self.clear().await
+    }
+
+    // 前回の処理中に停止した場合の復旧
+    // committed が true を返す作業単位は確定、それ以外は取り消す
+    pub(crate) fn recover(&self, committed: impl Fn(&str) -> bool) -> Result<(), BoxError> {
+        if !self.path.is_file() {
+            return Ok(());
+        }
+
+        let journal: JournalState = serde_json::from_slice(&std::fs::read(&self.path)?)?;
+        if committed(&journal.id) {
+            tracing::warn!("recover unit of work {}: roll forward", journal.id);
+            roll_forward(&journal.entries);
+        } else {
+            tracing::warn!("recover unit of work {}: roll back", journal.id);
+            roll_back(&journal.entries)?;
+        }
+        std::fs::remove_file(&self.path)?;
+        Ok(())
+    }
+
+    // 変更前のファイルを退避して記録する (同じファイルの2回目以降の変更は記録済みの退避先をそのまま使う)
+    // 退避の前に記録を保存しておく
+    async fn record(&self, journal: &mut JournalState, path: &Path) -> Result<(), BoxError> {
+        if journal.entries.iter().any(|e| e.path == path) {
+            return Ok(());
+        }
+
+        let exists = tokio::fs::try_exists(path).await?;
+        let backup = exists.then(|| sibling(path, &format!("{}.bak", journal.id)));
+        journal.entries.push(JournalEntry {
+            path: path.to_path_buf(),
+            backup: backup.clone(),
+        });
+        self.persist(journal).await?;
+
+        if let Some(backup) = backup {
+            tokio::fs::rename(path, &backup).await?;
+        }
+        Ok(())
+    }
+
+    async fn persist(&self, journal: &JournalState) -> Result<(), BoxError> {
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let temp = sibling(&self.path, "tmp");
+        tokio::fs::write(&temp, serde_json::to_vec(journal)?).await?;
+        tokio::fs::rename(&temp, &self.path).await?;
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<(), BoxError> {
+        match tokio::fs::remove_file(&self.path).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+}
+
+// 同じディレクトリの隠しファイル名 (.<name>.<suffix>)
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}", name, suffix))
+}
+
+fn remove_path(path: &Path) -> Result<(), BoxError> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(Box::new(e)),
+    }
+}
+
+fn roll_forward(entries: &[JournalEntry]) {
+    for backup in entries.iter().filter_map(|e| e.backup.as_ref()) {
+        if let Err(e) = remove_path(backup) {
+            tracing::warn!("failed to remove backup {}: {}", backup.display(), e);
+        }
+    }
+}
+
+fn roll_back(entries: &[JournalEntry]) -> Result<(), BoxError> {
+    for entry in entries.iter().rev() {
+        match &entry.backup {
+            // 退避前に停止した場合は元のファイルがそのまま残っている
+            Some(backup) if !backup.exists() => {}
+            Some(backup) => {
+                remove_path(&entry.path)?;
+                std::fs::rename(backup, &entry.path)?;
+            }
+            None => remove_path(&entry.path)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, FileJournal) {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = FileJournal::new(dir.path().join("journal.json"));
+        (dir, journal)
+    }
+
+    fn read(path: &Path) -> Option<String> {
+        std::fs::read_to_string(path).ok()
+    }
+
+    // 退避先・一時ファイル (. で始まるもの) の一覧
+    fn leftovers(dir: &Path) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.file_name().unwrap().to_string_lossy().starts_with('.') {
+                found.push(path.clone());
+            }
+            if path.is_dir() {
+                found.extend(leftovers(&path));
+            }
+        }
+        found
+    }
+
+    // 既存の a.txt を書き換え、b.txt を作成し、c.txt を削除する
+    async fn stage_files(dir: &Path, journal: &FileJournal) -> String {
+        std::fs::write(dir.join("a.txt"), "old").unwrap();
+        std::fs::write(dir.join("c.txt"), "removed").unwrap();
+
+        let id = journal.begin().await.unwrap();
+        journal.write(&dir.join("a.txt"), b"new").await.unwrap();
+        journal.write(&dir.join("a.txt"), b"newer").await.unwrap();
+        journal.write(&dir.join("b.txt"), b"created").await.unwrap();
+        assert!(journal.remove(&dir.join("c.txt")).await.unwrap());
+        assert!(!journal.remove(&dir.join("missing.txt")).await.unwrap());
+        id
+    }
+
+    fn assert_committed(dir: &Path) {
+        assert_eq!(read(&dir.join("a.txt")).as_deref(), Some("newer"));
+        assert_eq!(read(&dir.join("b.txt")).as_deref(), Some("created"));
+        assert!(!dir.join("c.txt").exists());
+        assert!(!dir.join("journal.json").exists());
+        assert_eq!(leftovers(dir), Vec::<PathBuf>::new());
+    }
+
+    fn assert_rolled_back(dir: &Path) {
+        assert_eq!(read(&dir.join("a.txt")).as_deref(), Some("old"));
+        assert!(!dir.join("b.txt").exists());
+        assert_eq!(read(&dir.join("c.txt")).as_deref(), Some("removed"));
+        assert!(!dir.join("journal.json").exists());
+        assert_eq!(leftovers(dir), Vec::<PathBuf>::new());
+    }
+
+    // prune_archives と同じく月のディレクトリを退避してから年のディレクトリを退避する
+    async fn stage_archives(dir: &Path, journal: &FileJournal) -> String {
+        let year = dir.join("archives").join("2024");
+        for month in ["05", "06"] {
+            std::fs::create_dir_all(year.join(month)).unwrap();
+            std::fs::write(year.join(month).join("index.html"), month).unwrap();
+        }
+
+        let id = journal.begin().await.unwrap();
+        assert!(journal.remove(&year.join("05")).await.unwrap());
+        assert!(journal.remove(&year.join("06")).await.unwrap());
+        assert!(journal.remove(&year).await.unwrap());
+        assert!(!year.exists());
+        id
+    }
+
+    fn assert_archives_restored(dir: &Path) {
+        let year = dir.join("archives").join("2024");
+        for month in ["05", "06"] {
+            assert_eq!(
+                read(&year.join(month).join("index.html")).as_deref(),
+                Some(month)
+            );
+        }
+        assert_eq!(leftovers(dir), Vec::<PathBuf>::new());
+    }
+
+    #[tokio::test]
+    async fn writes_directly_outside_unit_of_work() {
+        let (dir, journal) = setup();
+        journal
+            .write(&dir.path().join("a.txt"), b"new")
+            .await
+            .unwrap();
+        assert!(journal.remove(&dir.path().join("a.txt")).await.unwrap());
+
+        assert!(!dir.path().join("a.txt").exists());
+        assert!(!dir.path().join("journal.json").exists());
+        assert_eq!(leftovers(dir.path()), Vec::<PathBuf>::new());
+    }
+
+    #[tokio::test]
+    async fn commit_keeps_changes_and_removes_backups() {
+        let (dir, journal) = setup();
+        stage_files(dir.path(), &journal).await;
+        assert!(dir.path().join("journal.json").exists());
+
+        journal.commit().await.unwrap();
+        assert_committed(dir.path());
+        assert_eq!(journal.id().await, None);
+    }
+
+    #[tokio::test]
+    async fn rollback_restores_previous_files() {
+        let (dir, journal) = setup();
+        stage_files(dir.path(), &journal).await;
+
+        journal.rollback().await.unwrap();
+        assert_rolled_back(dir.path());
+        assert_eq!(journal.id().await, None);
+    }
+
+    #[tokio::test]
+    async fn begin_twice_is_rejected() {
+        let (_dir, journal) = setup();
+        let id = journal.begin().await.unwrap();
+        assert!(journal.begin().await.is_err());
+        assert_eq!(journal.id().await, Some(id));
+    }
+
+    #[tokio::test]
+    async fn recover_rolls_back_interrupted_unit_of_work() {
+        let (dir, journal) = setup();
+        stage_files(dir.path(), &journal).await;
+        drop(journal);
+
+        let journal = FileJournal::new(dir.path().join("journal.json"));
+        journal.recover(|_| false).unwrap();
+        assert_rolled_back(dir.path());
+    }
+
+    #[tokio::test]
+    async fn recover_rolls_forward_committed_unit_of_work() {
+        let (dir, journal) = setup();
+        let id = stage_files(dir.path(), &journal).await;
+        drop(journal);
+
+        let journal = FileJournal::new(dir.path().join("journal.json"));
+        journal.recover(|committed| committed == id).unwrap();
+        assert_committed(dir.path());
+    }
+
+    #[tokio::test]
+    async fn recover_without_journal_does_nothing() {
+        let (dir, journal) = setup();
+        std::fs::write(dir.path().join("a.txt"), "old").unwrap();
+        journal.recover(|_| false).unwrap();
+        assert_eq!(read(&dir.path().join("a.txt")).as_deref(), Some("old"));
+    }
+
+    #[tokio::test]
+    async fn rollback_restores_nested_archive_directories() {
+        let (dir, journal) = setup();
+        stage_archives(dir.path(), &journal).await;
+
+        journal.rollback().await.unwrap();
+        assert_archives_restored(dir.path());
+    }
+
+    #[tokio::test]
+    async fn commit_removes_nested_archive_directories() {
+        let (dir, journal) = setup();
+        stage_archives(dir.path(), &journal).await;
+
+        journal.commit().await.unwrap();
+        assert!(!dir.path().join("archives").join("2024").exists());
+        assert_eq!(leftovers(dir.path()), Vec::<PathBuf>::new());
+    }
+
+    #[tokio::test]
+    async fn recover_restores_nested_archive_directories() {
+        let (dir, journal) = setup();
+        stage_archives(dir.path(), &journal).await;
+        drop(journal);
+
+        let journal = FileJournal::new(dir.path().join("journal.json"));
+        journal.recover(|_| false).unwrap();
+        assert_archives_restored(dir.path());
+    }
+}
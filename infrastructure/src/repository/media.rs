@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::path::{Component, Path, PathBuf};
+use uuid::Uuid;
+
+use common::types::BoxError;
+use config::CONFIG;
+use domain::{model::media::MediaEntity, repository::media::MediaRepository};
+
+// MIME タイプと拡張子の対応
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("image/png", "png"),
+    ("image/jpeg", "jpg"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("image/avif", "avif"),
+    ("image/svg+xml", "svg"),
+    ("application/pdf", "pdf"),
+    ("application/zip", "zip"),
+    ("text/plain", "txt"),
+    ("text/csv", "csv"),
+];
+
+pub struct MediaRepositoryImpl {
+    output_path: PathBuf,
+    url_prefix: String,
+}
+
+impl Default for MediaRepositoryImpl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MediaRepositoryImpl {
+    pub fn new() -> Self {
+        Self {
+            output_path: PathBuf::from(CONFIG.content.html_dir.clone()).join(&CONFIG.media.dir),
+            url_prefix: format!("/{}", CONFIG.media.dir.trim_matches('/')),
+        }
+    }
+
+    fn extension(content_type: &str, file_name: &str) -> String {
+        if let Some((_, ext)) = EXTENSIONS.iter().find(|(mime, _)| *mime == content_type) {
+            return ext.to_string();
+        }
+
+        // 対応表に無い場合は元のファイル名の拡張子(英数字のみ)を使う
+        Path::new(file_name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .filter(|ext| !ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or_else(|| "bin".to_string())
+    }
+
+    fn content_type(path: &Path) -> String {
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .unwrap_or_default();
+        EXTENSIONS
+            .iter()
+            .find(|(_, e)| *e == ext)
+            .map(|(mime, _)| mime.to_string())
+            .unwrap_or_else(|| "application/octet-stream".to_string())
+    }
+
+    async fn to_entity(&self, path: &Path) -> Result<MediaEntity, BoxError> {
+        let name = path
+            .strip_prefix(&self.output_path)?
+            .components()
+            .filter_map(|c| c.as_os_str().to_str())
+            .collect::<Vec<_>>()
+            .join("/");
+        let metadata = tokio::fs::metadata(path).await?;
+        let updated_at: DateTime<Utc> = metadata.modified()?.into();
+
+        Ok(MediaEntity {
+            url: format!("{}/{}", self.url_prefix, name),
+            content_type: Self::content_type(path),
+            size: metadata.len(),
+            updated_at,
+            name,
+        })
+    }
+}
+
+#[async_trait]
+impl MediaRepository for MediaRepositoryImpl {
+    async fn create(
+        &self,
+        content_type: &str,
+        file_name: &str,
+        data: &[u8],
+    ) -> Result<MediaEntity, BoxError> {
+        let dir = self.output_path.join(Utc::now().format("%Y%m").to_string());
+        tokio::fs::create_dir_all(&dir).await?;
+
+        let file_path = dir.join(format!(
+            "{}.{}",
+            Uuid::new_v4(),
+            Self::extension(content_type, file_name)
+        ));
+        tokio::fs::write(&file_path, data).await?;
+
+        self.to_entity(&file_path).await
+    }
+
+    async fn list(&self) -> Result<Vec<MediaEntity>, BoxError> {
+        if !self.output_path.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut entities = Vec::new();
+        let mut stack = vec![self.output_path.clone()];
+
+        while let Some(dir) = stack.pop() {
+            let mut rd = tokio::fs::read_dir(&dir).await?;
+            while let Some(entry) = rd.next_entry().await? {
+                let path = entry.path();
+                if path.is_dir() {
+                    stack.push(path);
+                } else if path.is_file() {
+                    entities.push(self.to_entity(&path).await?);
+                }
+            }
+        }
+
+        entities.sort_by_key(|e| std::cmp::Reverse(e.updated_at));
+        Ok(entities)
+    }
+
+    async fn remove(&self, name: &str) -> Result<u64, BoxError> {
+        // media ディレクトリの外を指すパスは受け付けない
+        let relative = Path::new(name);
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return Ok(0);
+        }
+
+        let file_path = self.output_path.join(relative);
+        match tokio::fs::remove_file(&file_path).await {
+            Ok(_) => Ok(1),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+}
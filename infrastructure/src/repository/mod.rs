@@ -1,5 +1,13 @@
 pub mod content;
+pub mod feed;
 pub mod html_parser;
 pub mod index_writer_handle;
+pub mod journal;
+pub mod media;
 pub mod member;
+pub mod page;
+pub mod query_syntax;
+pub mod revision;
 pub mod search_engine;
+pub mod sitemap;
+pub mod unit_of_work;
@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::repository::{
+    content::{join_front_matter, split_front_matter},
+    journal::FileJournal,
+};
+use common::types::BoxError;
+use config::CONFIG;
+use domain::{
+    model::page::{PageEntity, PageMatterEntity},
+    repository::page::PageRepository,
+};
+
+pub struct PageRepositoryImpl {
+    output_path: PathBuf,
+    journal: Arc<FileJournal>,
+}
+
+impl PageRepositoryImpl {
+    pub(crate) fn new(journal: Arc<FileJournal>) -> Self {
+        Self {
+            output_path: PathBuf::from(CONFIG.content.page_dir.clone()),
+            journal,
+        }
+    }
+
+    // page_dir/<id>.md (ディレクトリの外を指す id は受け付けない)
+    fn file_path(&self, id: &str) -> Option<PathBuf> {
+        let mut components = Path::new(id).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => Some(self.output_path.join(format!("{}.md", id))),
+            _ => None,
+        }
+    }
+
+    async fn parse(file_path: &Path) -> Result<PageEntity, BoxError> {
+        let id = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| BoxError::from(format!("Invalid file name: {}", file_path.display())))?;
+
+        let content = tokio::fs::read_to_string(file_path).await?;
+        let (matter_yaml, body) = split_front_matter(&content, file_path)?;
+        let matter: PageMatterEntity = serde_yaml::from_str(matter_yaml)?;
+
+        Ok(PageEntity {
+            id: id.to_string(),
+            matter,
+            body: body.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl PageRepository for PageRepositoryImpl {
+    async fn create(&self, entity: &PageEntity) -> Result<PageEntity, BoxError> {
+        tracing::debug!("create page: {:?}", entity);
+
+        let file_path = self
+            .file_path(&entity.id)
+            .ok_or_else(|| BoxError::from(format!("Invalid page id: {}", entity.id)))?;
+        let file_content = join_front_matter(&entity.matter, &entity.body)?;
+        self.journal
+            .write(&file_path, file_content.as_bytes())
+            .await?;
+
+        Ok(entity.clone())
+    }
+
+    async fn find(&self, id: &str) -> Result<Option<PageEntity>, BoxError> {
+        let Some(file_path) = self.file_path(id) else {
+            return Ok(None);
+        };
+        if !file_path.is_file() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self::parse(&file_path).await?))
+    }
+
+    // メニューの並び順 (order, id の昇順)
+    async fn list(&self) -> Result<Vec<PageEntity>, BoxError> {
+        if !self.output_path.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut entities = Vec::new();
+        let mut rd = tokio::fs::read_dir(&self.output_path).await?;
+        while let Some(entry) = rd.next_entry().await? {
+            let path = entry.path();
+            let is_markdown = path.is_file() && path.extension().is_some_and(|ext| ext == "md");
+            if !is_markdown {
+                continue;
+            }
+            match Self::parse(&path).await {
+                Ok(entity) => entities.push(entity),
+                Err(e) => tracing::warn!("skip broken page {}: {}", path.display(), e),
+            }
+        }
+
+        entities.sort_by(|a, b| (a.matter.order, &a.id).cmp(&(b.matter.order, &b.id)));
+        Ok(entities)
+    }
+
+    async fn remove(&self, id: &str) -> Result<u64, BoxError> {
+        let Some(file_path) = self.file_path(id) else {
+            return Ok(0);
+        };
+        let removed = self.journal.remove(&file_path).await?;
+        Ok(removed as u64)
+    }
+}
@@ -0,0 +1,382 @@
+use domain::model::search_engine::QuerySyntaxError;
+
+// 高度な検索構文
+//   語句                  タイトル・説明・本文のいずれかに一致
+//   "語句 語句"           フレーズ (並びどおりに一致)
+//   +語句 / -語句         必ず含む / 含まない
+//   title: tag: category: 対象の項目を限定 (tag: と category: は値全体に一致)
+//   語句*                 前方一致
+//   語句~ / 語句~2        あいまい一致 (英数字のみ、編集距離 1 または 2)
+// 語句は空白 (全角空白を含む) で区切る
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Occurrence {
+    Should,
+    Must,
+    MustNot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Scope {
+    All,
+    Title,
+    Tag,
+    Category,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Atom {
+    Word(String),
+    Phrase(String),
+    Prefix(String),
+    Fuzzy(String, u8),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Clause {
+    pub occurrence: Occurrence,
+    pub scope: Scope,
+    pub atom: Atom,
+}
+
+pub(crate) fn parse(input: &str) -> Result<Vec<Clause>, QuerySyntaxError> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let is_end = |i: usize| i >= chars.len() || chars[i].is_whitespace();
+    let mut clauses = Vec::new();
+    let mut i = 0;
+
+    loop {
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        if i >= chars.len() {
+            break;
+        }
+
+        let occurrence = match chars[i] {
+            '+' => Occurrence::Must,
+            '-' => Occurrence::MustNot,
+            _ => Occurrence::Should,
+        };
+        if occurrence != Occurrence::Should {
+            i += 1;
+            if is_end(i) {
+                return Err(error(i, "operator must be followed by a term"));
+            }
+        }
+
+        let mut scope = Scope::All;
+        let mut j = i;
+        while j < chars.len() && chars[j].is_ascii_alphabetic() {
+            j += 1;
+        }
+        if j > i && j < chars.len() && chars[j] == ':' {
+            let name = chars[i..j].iter().collect::<String>();
+            scope = match name.as_str() {
+                "title" => Scope::Title,
+                "tag" => Scope::Tag,
+                "category" => Scope::Category,
+                _ => return Err(error(i + 1, &format!("unknown field '{}'", name))),
+            };
+            i = j + 1;
+            if is_end(i) {
+                return Err(error(i, "field must be followed by a term"));
+            }
+        }
+
+        let atom = if chars[i] == '"' {
+            let Some(close) = (i + 1..chars.len()).find(|&k| chars[k] == '"') else {
+                return Err(error(i + 1, "unterminated phrase"));
+            };
+            let phrase = chars[i + 1..close].iter().collect::<String>();
+            if phrase.trim().is_empty() {
+                return Err(error(i + 1, "empty phrase"));
+            }
+            i = close + 1;
+            if !is_end(i) {
+                return Err(error(
+                    i + 1,
+                    &format!("unexpected character '{}'", chars[i]),
+                ));
+            }
+            Atom::Phrase(phrase)
+        } else {
+            let start = i;
+            while !is_end(i) {
+                i += 1;
+            }
+            word_atom(&chars[start..i], start)?
+        };
+
+        clauses.push(Clause {
+            occurrence,
+            scope,
+            atom,
+        });
+    }
+
+    Ok(clauses)
+}
+
+// start は word の先頭の (0 から数えた) 位置
+fn word_atom(word: &[char], start: usize) -> Result<Atom, QuerySyntaxError> {
+    let special = |k: usize| matches!(word[k], '"' | '*' | '~');
+    let last = word.len() - 1;
+
+    if word[last] == '*' {
+        if let Some(k) = (0..last).find(|&k| special(k)) {
+            return Err(error(
+                start + k + 1,
+                &format!("unexpected character '{}'", word[k]),
+            ));
+        }
+        if last == 0 {
+            return Err(error(start + 1, "prefix must not be empty"));
+        }
+        return Ok(Atom::Prefix(word[..last].iter().collect()));
+    }
+
+    if let Some(tilde) = word.iter().position(|&c| c == '~') {
+        if let Some(k) = (0..tilde).find(|&k| special(k)) {
+            return Err(error(
+                start + k + 1,
+                &format!("unexpected character '{}'", word[k]),
+            ));
+        }
+        let distance = match word[tilde + 1..].iter().collect::<String>().as_str() {
+            "" | "1" => 1,
+            "2" => 2,
+            _ => return Err(error(start + tilde + 2, "fuzzy distance must be 1 or 2")),
+        };
+        if tilde == 0 {
+            return Err(error(start + 1, "fuzzy term must not be empty"));
+        }
+        // ローマ字・英語の綴りの揺れを対象にする
+        if !word[..tilde].iter().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(error(
+                start + 1,
+                "fuzzy matching is only available for alphanumeric terms",
+            ));
+        }
+        return Ok(Atom::Fuzzy(word[..tilde].iter().collect(), distance));
+    }
+
+    if let Some(k) = (0..word.len()).find(|&k| special(k)) {
+        return Err(error(
+            start + k + 1,
+            &format!("unexpected character '{}'", word[k]),
+        ));
+    }
+    Ok(Atom::Word(word.iter().collect()))
+}
+
+fn error(position: usize, message: &str) -> QuerySyntaxError {
+    QuerySyntaxError {
+        position,
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clause(occurrence: Occurrence, scope: Scope, atom: Atom) -> Clause {
+        Clause {
+            occurrence,
+            scope,
+            atom,
+        }
+    }
+
+    fn word(text: &str) -> Atom {
+        Atom::Word(text.to_string())
+    }
+
+    fn error_at(input: &str) -> (usize, String) {
+        let e = parse(input).unwrap_err();
+        (e.position, e.message)
+    }
+
+    #[test]
+    fn parses_words_separated_by_whitespace() {
+        assert_eq!(parse("").unwrap(), vec![]);
+        assert_eq!(
+            parse(" rust\u{3000}axum ").unwrap(),
+            vec![
+                clause(Occurrence::Should, Scope::All, word("rust")),
+                clause(Occurrence::Should, Scope::All, word("axum")),
+            ]
+        );
+        // 英字以外の ':' は項目の指定として扱わない
+        assert_eq!(
+            parse("12:30").unwrap(),
+            vec![clause(Occurrence::Should, Scope::All, word("12:30"))]
+        );
+    }
+
+    #[test]
+    fn parses_phrases() {
+        assert_eq!(
+            parse("\"hello world\" 日本語").unwrap(),
+            vec![
+                clause(
+                    Occurrence::Should,
+                    Scope::All,
+                    Atom::Phrase("hello world".to_string())
+                ),
+                clause(Occurrence::Should, Scope::All, word("日本語")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_operators() {
+        assert_eq!(
+            parse("+rust -java \"-x\"").unwrap(),
+            vec![
+                clause(Occurrence::Must, Scope::All, word("rust")),
+                clause(Occurrence::MustNot, Scope::All, word("java")),
+                clause(
+                    Occurrence::Should,
+                    Scope::All,
+                    Atom::Phrase("-x".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_field_scopes() {
+        assert_eq!(
+            parse("title:rust +tag:Web -category:\"foo bar\"").unwrap(),
+            vec![
+                clause(Occurrence::Should, Scope::Title, word("rust")),
+                clause(Occurrence::Must, Scope::Tag, word("Web")),
+                clause(
+                    Occurrence::MustNot,
+                    Scope::Category,
+                    Atom::Phrase("foo bar".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_prefixes() {
+        assert_eq!(
+            parse("rus* -tag:draft*").unwrap(),
+            vec![
+                clause(
+                    Occurrence::Should,
+                    Scope::All,
+                    Atom::Prefix("rus".to_string())
+                ),
+                clause(
+                    Occurrence::MustNot,
+                    Scope::Tag,
+                    Atom::Prefix("draft".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_fuzzy_terms() {
+        assert_eq!(
+            parse("colour~ colour~1 title:colour~2").unwrap(),
+            vec![
+                clause(
+                    Occurrence::Should,
+                    Scope::All,
+                    Atom::Fuzzy("colour".to_string(), 1)
+                ),
+                clause(
+                    Occurrence::Should,
+                    Scope::All,
+                    Atom::Fuzzy("colour".to_string(), 1)
+                ),
+                clause(
+                    Occurrence::Should,
+                    Scope::Title,
+                    Atom::Fuzzy("colour".to_string(), 2)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_operator_errors() {
+        let message = "operator must be followed by a term".to_string();
+        assert_eq!(error_at("+"), (1, message.clone()));
+        assert_eq!(error_at("foo - bar"), (5, message));
+    }
+
+    #[test]
+    fn reports_field_errors() {
+        assert_eq!(
+            error_at("a author:x"),
+            (3, "unknown field 'author'".to_string())
+        );
+        assert_eq!(
+            error_at("Title:x"),
+            (1, "unknown field 'Title'".to_string())
+        );
+        assert_eq!(
+            error_at("+title: x"),
+            (7, "field must be followed by a term".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_phrase_errors() {
+        assert_eq!(error_at("a \"b c"), (3, "unterminated phrase".to_string()));
+        assert_eq!(error_at("\" \""), (1, "empty phrase".to_string()));
+        assert_eq!(
+            error_at("\"ab\"c"),
+            (5, "unexpected character 'c'".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_prefix_errors() {
+        assert_eq!(
+            error_at("x a*b*"),
+            (4, "unexpected character '*'".to_string())
+        );
+        assert_eq!(
+            error_at("title:*"),
+            (7, "prefix must not be empty".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_fuzzy_errors() {
+        assert_eq!(
+            error_at("a\"b~"),
+            (2, "unexpected character '\"'".to_string())
+        );
+        assert_eq!(
+            error_at("abc~3"),
+            (5, "fuzzy distance must be 1 or 2".to_string())
+        );
+        assert_eq!(
+            error_at("x ~2"),
+            (3, "fuzzy term must not be empty".to_string())
+        );
+        assert_eq!(
+            error_at("x 日本~1"),
+            (
+                3,
+                "fuzzy matching is only available for alphanumeric terms".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn reports_unexpected_characters_in_words() {
+        assert_eq!(
+            error_at("ab\"c"),
+            (3, "unexpected character '\"'".to_string())
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::repository::journal::FileJournal;
+use common::types::BoxError;
+use config::CONFIG;
+use domain::{model::revision::RevisionEntity, repository::revision::RevisionRepository};
+
+pub struct RevisionRepositoryImpl {
+    output_path: PathBuf,
+    journal: Arc<FileJournal>,
+}
+
+impl RevisionRepositoryImpl {
+    pub(crate) fn new(journal: Arc<FileJournal>) -> Self {
+        Self {
+            output_path: PathBuf::from(CONFIG.content.revision_dir.clone()),
+            journal,
+        }
+    }
+
+    // 記事ごとのディレクトリ (revision_dir/<id>/<revision>.json)
+    // ディレクトリの外を指す id は受け付けない
+    fn content_dir(&self, id: &str) -> Option<PathBuf> {
+        let mut components = Path::new(id).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => Some(self.output_path.join(id)),
+            _ => None,
+        }
+    }
+
+    fn is_valid_revision(revision: &str) -> bool {
+        !revision.is_empty() && revision.chars().all(|c| c.is_ascii_digit())
+    }
+}
+
+#[async_trait]
+impl RevisionRepository for RevisionRepositoryImpl {
+    async fn create(&self, entity: &RevisionEntity) -> Result<RevisionEntity, BoxError> {
+        let dir = self
+            .content_dir(&entity.content.id)
+            .ok_or_else(|| BoxError::from(format!("Invalid content id: {}", entity.content.id)))?;
+        if !Self::is_valid_revision(&entity.revision) {
+            return Err(BoxError::from(format!(
+                "Invalid revision: {}",
+                entity.revision
+            )));
+        }
+        let file_path = dir.join(format!("{}.json", entity.revision));
+        self.journal
+            .write(&file_path, &serde_json::to_vec_pretty(entity)?)
+            .await?;
+
+        Ok(entity.clone())
+    }
+
+    async fn find(&self, id: &str, revision: &str) -> Result<Option<RevisionEntity>, BoxError> {
+        let Some(dir) = self.content_dir(id) else {
+            return Ok(None);
+        };
+        if !Self::is_valid_revision(revision) {
+            return Ok(None);
+        }
+
+        let file_path = dir.join(format!("{}.json", revision));
+        match tokio::fs::read(&file_path).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    // 新しい順
+    async fn list(&self, id: &str) -> Result<Vec<RevisionEntity>, BoxError> {
+        let Some(dir) = self.content_dir(id) else {
+            return Ok(Vec::new());
+        };
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut entities = Vec::new();
+        let mut rd = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = rd.next_entry().await? {
+            let path = entry.path();
+            let is_json = path.is_file() && path.extension().is_some_and(|ext| ext == "json");
+            if !is_json {
+                continue;
+            }
+            let data = tokio::fs::read(&path).await?;
+            match serde_json::from_slice::<RevisionEntity>(&data) {
+                Ok(entity) => entities.push(entity),
+                Err(e) => tracing::warn!("skip broken revision {}: {}", path.display(), e),
+            }
+        }
+
+        entities.sort_by(|a, b| b.revision.cmp(&a.revision));
+        Ok(entities)
+    }
+
+    // 新しいものから keep 件を残して削除する
+    async fn prune(&self, id: &str, keep: usize) -> Result<u64, BoxError> {
+        let Some(dir) = self.content_dir(id) else {
+            return Ok(0);
+        };
+
+        let mut deleted = 0u64;
+        for entity in self.list(id).await?.into_iter().skip(keep) {
+            let file_path = dir.join(format!("{}.json", entity.revision));
+            if self.journal.remove(&file_path).await? {
+                deleted += 1;
+            }
+        }
+
+        Ok(deleted)
+    }
+}
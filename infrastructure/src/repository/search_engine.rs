@@ -1,32 +1,61 @@
 use async_trait::async_trait;
-use chrono::TimeZone;
+use chrono::{DateTime, Datelike, TimeZone, Utc};
 use config::CONFIG;
 use lindera::{mode::Mode, segmenter::Segmenter};
 use lindera_tantivy::tokenizer::LinderaTokenizer;
 use scraper::{ElementRef, Html, Selector};
-use std::path::PathBuf;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 use tantivy::{
-    DocAddress, Index, IndexSettings, Order, TantivyDocument, Term,
-    collector::{Count, TopDocs},
+    DocAddress, Index, IndexSettings, IndexWriter, Order, Searcher, TantivyDocument, Term,
+    collector::{Count, DocSetCollector, TopDocs},
     directory::MmapDirectory,
     doc,
-    query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, TermQuery},
+    query::{
+        AllQuery, BooleanQuery, BoostQuery, ConstScoreQuery, EmptyQuery, FuzzyTermQuery,
+        MoreLikeThisQuery, Occur, PhrasePrefixQuery, PhraseQuery, Query, QueryParser, TermQuery,
+    },
     schema::{
-        FAST, Field, INDEXED, IndexRecordOption, STORED, Schema, TextFieldIndexing, TextOptions,
-        Value,
+        FAST, Field, INDEXED, IndexRecordOption, OwnedValue, STORED, Schema, TextFieldIndexing,
+        TextOptions, Value,
     },
+    snippet::SnippetGenerator,
 };
 
-use crate::repository::index_writer_handle::{IndexWriterHandle, spawn_index_writer_task};
+use crate::repository::{
+    html_parser::HtmlParserRepositoryImpl,
+    index_writer_handle::{IndexWriterHandle, spawn_index_writer_task},
+    query_syntax::{self, Atom, Occurrence, Scope},
+};
 use common::types::BoxError;
 use domain::{
     model::{
-        content::{ContentEntity, FrontMatterEntity},
-        search_engine::{SearchParams, SearchResult},
+        content::{ContentEntity, FrontMatterEntity, assign_unique_slug},
+        search_engine::{
+            MonthCount, SearchFacets, SearchHighlight, SearchParams, SearchResult, SearchSort,
+            SuggestField,
+        },
     },
-    repository::search_engine::SearchEngineRepository,
+    repository::{content::ContentRepository, search_engine::SearchEngineRepository},
 };
 
+// インデックスのスキーマの版 (initialize_schema を変更した場合は上げる)
+// index_dir/v{版} に保存し、版が変わった場合は起動時に markdown_dir から作り直して切り替える
+const SCHEMA_VERSION: u32 = 3;
+// 移行の進捗をログに出す間隔 (ファイル数)
+const MIGRATE_LOG_INTERVAL: usize = 100;
+
+// 関連記事のスコアの重み (タグ・カテゴリーの一致をタイトル・本文の語句より重視する)
+const RELATED_TAG_BOOST: f32 = 3.0;
+const RELATED_CATEGORY_BOOST: f32 = 2.0;
+// タイトル・本文から取り出す語句の最大数
+const RELATED_MAX_TERMS: usize = 25;
+
+// 入力候補のタイトルの語句は、辞書の件数の多い順に limit × この数だけ数え直す
+const SUGGEST_CANDIDATE_FACTOR: usize = 5;
+
 #[derive(Clone)]
 struct SchemaFields {
     pub id: Field,
@@ -37,6 +66,8 @@ struct SchemaFields {
     pub date: Field,
     pub tags: Field,
     pub categories: Field,
+    pub slug: Field,
+    pub title_sort: Field, // タイトル順の並べ替え用 (分かち書きしない)
 }
 
 #[allow(dead_code)]
@@ -49,7 +80,7 @@ pub struct SearchEngineRepositoryImpl {
 }
 
 impl SearchEngineRepositoryImpl {
-    pub fn new() -> Result<Self, BoxError> {
+    pub async fn new(contents: &dyn ContentRepository) -> Result<Self, BoxError> {
         tracing::debug!(
             "initialize search engine > index: {}, dictionary: {}, memory_budget: {}, index_limit: {}, search_limit: {}",
             CONFIG.search.index_dir.clone(),
@@ -60,23 +91,24 @@ impl SearchEngineRepositoryImpl {
         );
 
         let index_dir = PathBuf::from(CONFIG.search.index_dir.clone());
-
         if !index_dir.exists() {
             std::fs::create_dir_all(&index_dir)?;
         }
-        let directory = MmapDirectory::open(&index_dir)?;
+        Self::recover_swap(&index_dir)?;
 
-        let index = if Index::exists(&directory)? {
-            Index::open(directory)?
-        } else {
-            let schema = Self::initialize_schema()?;
-            Index::create(directory, schema.clone(), IndexSettings::default())?
+        let schema = Self::initialize_schema()?;
+        let current = Self::version_dir(&index_dir, SCHEMA_VERSION);
+        let up_to_date = match Self::open_index(&current)? {
+            Some(index) => index.schema() == schema,
+            None => false,
         };
+        if !up_to_date {
+            Self::migrate(&index_dir, schema, contents).await?;
+        }
 
-        index.tokenizers().register(
-            "lang_ja",
-            Self::get_tokenizer(&CONFIG.search.dictionary_dir.clone())?,
-        );
+        let index = Self::open_index(&current)?
+            .ok_or_else(|| format!("index not found: {}", current.display()))?;
+        Self::register_tokenizers(&index)?;
         let fields = Self::schema_to_fields(&index.schema())?;
 
         let writer = index.writer(CONFIG.search.memory_budget_in_bytes)?;
@@ -91,6 +123,245 @@ impl SearchEngineRepositoryImpl {
         })
     }
 
+    fn version_dir(index_dir: &Path, version: u32) -> PathBuf {
+        index_dir.join(format!("v{}", version))
+    }
+
+    fn open_index(dir: &Path) -> Result<Option<Index>, BoxError> {
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+        let directory = MmapDirectory::open(dir)?;
+        if !Index::exists(&directory)? {
+            return Ok(None);
+        }
+        Ok(Some(Index::open(directory)?))
+    }
+
+    // 現在の版が無い場合は、残っている最も新しい版 (版を付ける前は index_dir 直下) を使う
+    fn existing_index_dir(index_dir: &Path) -> Result<Option<PathBuf>, BoxError> {
+        let current = Self::version_dir(index_dir, SCHEMA_VERSION);
+        if Self::open_index(&current)?.is_some() {
+            return Ok(Some(current));
+        }
+
+        let mut versions = Vec::new();
+        for entry in std::fs::read_dir(index_dir)? {
+            let path = entry?.path();
+            let version = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix('v'))
+                .and_then(|n| n.parse::<u32>().ok());
+            if let Some(version) = version {
+                versions.push((version, path));
+            }
+        }
+        versions.sort();
+        for (_, path) in versions.into_iter().rev() {
+            if Self::open_index(&path)?.is_some() {
+                return Ok(Some(path));
+            }
+        }
+
+        if Self::open_index(index_dir)?.is_some() {
+            return Ok(Some(index_dir.to_path_buf()));
+        }
+        Ok(None)
+    }
+
+    // 起動時の復旧に使う、最後に確定したコミットに付けた作業単位の id
+    // 移行前は旧版のインデックスから読む (移行は復旧後の markdown_dir から行う)
+    pub(crate) fn startup_commit_payload() -> Result<Option<String>, BoxError> {
+        let index_dir = PathBuf::from(CONFIG.search.index_dir.clone());
+        if !index_dir.is_dir() {
+            return Ok(None);
+        }
+        Self::recover_swap(&index_dir)?;
+
+        let Some(dir) = Self::existing_index_dir(&index_dir)? else {
+            return Ok(None);
+        };
+        let index = Index::open(MmapDirectory::open(&dir)?)?;
+        Ok(index.load_metas()?.payload)
+    }
+
+    // 新しい版のインデックスを v{版}.migrating に作成し、完成してから v{版} に切り替える
+    // 旧版のインデックスは切り替えが済むまで残す
+    async fn migrate(
+        index_dir: &Path,
+        schema: Schema,
+        contents: &dyn ContentRepository,
+    ) -> Result<(), BoxError> {
+        let current = Self::version_dir(index_dir, SCHEMA_VERSION);
+        let work = index_dir.join(format!("v{}.migrating", SCHEMA_VERSION));
+        let source = Self::existing_index_dir(index_dir)?;
+
+        // 前回の移行の途中で停止していた場合は作り直す
+        if work.exists() {
+            std::fs::remove_dir_all(&work)?;
+        }
+        std::fs::create_dir_all(&work)?;
+        let index = Index::create(
+            MmapDirectory::open(&work)?,
+            schema,
+            IndexSettings::default(),
+        )?;
+        Self::register_tokenizers(&index)?;
+        let mut writer: IndexWriter = index.writer(CONFIG.search.memory_budget_in_bytes)?;
+
+        // 初回起動 (インデックスが無い) の場合は空のインデックスを作るだけ
+        if let Some(source) = &source {
+            tracing::warn!(
+                "index schema is out of date (version {}), migrating from {} into {}",
+                SCHEMA_VERSION,
+                source.display(),
+                work.display()
+            );
+            let fields = Self::schema_to_fields(&index.schema())?;
+            Self::migrate_contents(&mut writer, &fields, contents).await?;
+        }
+
+        tokio::task::spawn_blocking(move || -> Result<(), BoxError> {
+            writer.commit()?;
+            writer.wait_merging_threads()?;
+            Ok(())
+        })
+        .await??;
+        drop(index);
+
+        Self::swap(&work, &current)?;
+        if let Some(source) = &source {
+            tracing::info!(
+                "index migrated to schema version {}: {}",
+                SCHEMA_VERSION,
+                current.display()
+            );
+            if source != &current {
+                Self::remove_old_indexes(index_dir)?;
+            }
+        }
+        Ok(())
+    }
+
+    // rebuild と同じく、find() で引ける配置の Markdown を id の重複なく登録する
+    async fn migrate_contents(
+        writer: &mut IndexWriter,
+        fields: &SchemaFields,
+        contents: &dyn ContentRepository,
+    ) -> Result<(), BoxError> {
+        if !Path::new(&CONFIG.content.markdown_dir).is_dir() {
+            return Ok(());
+        }
+
+        let paths = contents.list().await?;
+        let (mut indexed, mut skipped, mut failed) = (0usize, 0usize, 0usize);
+        let mut seen = HashSet::new();
+        let mut slugs = HashSet::new();
+
+        for (i, path) in paths.iter().enumerate() {
+            if i > 0 && i % MIGRATE_LOG_INTERVAL == 0 {
+                tracing::info!("migrating index: {}/{}", i, paths.len());
+            }
+
+            let mut entity = match contents.load(path).await {
+                Ok(Some(entity)) => entity,
+                Ok(None) => {
+                    skipped += 1;
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("migrate failed to load {}: {}", path, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+            if !seen.insert(entity.id.clone()) {
+                tracing::warn!("migrate skipped duplicate id {}: {}", entity.id, path);
+                skipped += 1;
+                continue;
+            }
+            // rebuild と同じく、同じ slug の後の記事に連番を付ける
+            if assign_unique_slug(&mut entity.matter.slug, &mut slugs) {
+                tracing::warn!("migrate renamed duplicate slug of {}", path);
+            }
+            if indexed >= CONFIG.search.index_limit {
+                tracing::warn!(
+                    "migrate skipped {}: index limit exceeded (limit = {})",
+                    path,
+                    CONFIG.search.index_limit
+                );
+                failed += 1;
+                continue;
+            }
+
+            // 記事の保存時と同じく Markdown を HTML にしてからタグを除いた本文を登録する
+            let body = strip_tags(&HtmlParserRepositoryImpl::markdown_to_html(&entity.body))?;
+            writer.add_document(Self::to_document(fields, &ContentEntity { body, ..entity }))?;
+            indexed += 1;
+        }
+
+        tracing::info!(
+            "migrating index: {}/{} (indexed {}, skipped {}, failed {})",
+            paths.len(),
+            paths.len(),
+            indexed,
+            skipped,
+            failed
+        );
+        Ok(())
+    }
+
+    // 同じ版を作り直した場合は既存の v{版} を v{版}.old に退避してから置き換える
+    fn swap(work: &Path, current: &Path) -> Result<(), BoxError> {
+        let backup = current.with_extension("old");
+        if current.exists() {
+            if backup.exists() {
+                std::fs::remove_dir_all(&backup)?;
+            }
+            std::fs::rename(current, &backup)?;
+        }
+        std::fs::rename(work, current)?;
+        if backup.exists() {
+            std::fs::remove_dir_all(&backup)?;
+        }
+        Ok(())
+    }
+
+    // 切り替えの途中 (退避した直後) で停止していた場合は退避したインデックスに戻す
+    fn recover_swap(index_dir: &Path) -> Result<(), BoxError> {
+        let current = Self::version_dir(index_dir, SCHEMA_VERSION);
+        let backup = current.with_extension("old");
+        if !current.exists() && backup.is_dir() {
+            tracing::warn!(
+                "restore index interrupted while swapping: {}",
+                backup.display()
+            );
+            std::fs::rename(&backup, &current)?;
+        }
+        Ok(())
+    }
+
+    // 現在の版以外のインデックス (旧版のディレクトリと、版を付ける前の index_dir 直下のファイル) を削除する
+    fn remove_old_indexes(index_dir: &Path) -> Result<(), BoxError> {
+        let current = format!("v{}", SCHEMA_VERSION);
+        let legacy = Index::exists(&MmapDirectory::open(index_dir)?)?;
+
+        for entry in std::fs::read_dir(index_dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if path.is_dir() && name.starts_with('v') && name != current {
+                tracing::info!("remove old index: {}", path.display());
+                std::fs::remove_dir_all(&path)?;
+            } else if path.is_file() && legacy {
+                std::fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+
     #[rustfmt::skip]
     fn initialize_schema() -> Result<Schema, BoxError> {
         let mut builder = Schema::builder();
@@ -103,10 +374,12 @@ impl SearchEngineRepositoryImpl {
         let _ = builder.add_text_field("title", token_match.clone());
         let _ = builder.add_text_field("description", token_match.clone());
         let _ = builder.add_text_field("body", token_match.clone());
-        let _ = builder.add_bool_field("draft", STORED | INDEXED);
+        let _ = builder.add_bool_field("draft", STORED | INDEXED | FAST);
         let _ = builder.add_date_field("date", STORED | INDEXED | FAST);
-        let _ = builder.add_text_field("tags", full_match.clone());
-        let _ = builder.add_text_field("categories", full_match.clone());
+        let _ = builder.add_text_field("tags", full_match_sort.clone());
+        let _ = builder.add_text_field("categories", full_match_sort.clone());
+        let _ = builder.add_text_field("slug", full_match.clone());
+        let _ = builder.add_text_field("title_sort", Self::get_text_field_options("raw", IndexRecordOption::Basic, false, true));
         Ok(builder.build())
     }
 
@@ -130,6 +403,15 @@ impl SearchEngineRepositoryImpl {
         opts
     }
 
+    // title 等のフィールドが使うトークナイザー (インデックスを開くたびに登録する)
+    fn register_tokenizers(index: &Index) -> Result<(), BoxError> {
+        index.tokenizers().register(
+            "lang_ja",
+            Self::get_tokenizer(&CONFIG.search.dictionary_dir.clone())?,
+        );
+        Ok(())
+    }
+
     fn get_tokenizer(dictionary_path: &str) -> Result<LinderaTokenizer, BoxError> {
         let segmenter = Segmenter::new(
             Mode::Normal,
@@ -149,6 +431,8 @@ impl SearchEngineRepositoryImpl {
             date: schema.get_field("date")?,
             tags: schema.get_field("tags")?,
             categories: schema.get_field("categories")?,
+            slug: schema.get_field("slug")?,
+            title_sort: schema.get_field("title_sort")?,
         })
     }
 
@@ -156,7 +440,9 @@ impl SearchEngineRepositoryImpl {
         let mut queries: Vec<(Occur, Box<dyn Query>)> = Vec::new();
 
         // free word
-        if let Some(word) = &params.word {
+        if let (Some(word), true) = (&params.word, params.advanced) {
+            queries.push((Occur::Must, self.build_advanced_query(word)?));
+        } else if let Some(word) = &params.word {
             // 全角空白があるとエラーになるので対応
             let normalize = word
                 .replace('\u{3000}', " ")
@@ -267,6 +553,89 @@ impl SearchEngineRepositoryImpl {
         }
     }
 
+    // 高度な検索構文 (query_syntax) の検索語を検索条件にする
+    fn build_advanced_query(&self, word: &str) -> Result<Box<dyn Query>, BoxError> {
+        let mut queries: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for clause in query_syntax::parse(word)? {
+            let fields = match clause.scope {
+                Scope::All => vec![self.fields.title, self.fields.description, self.fields.body],
+                Scope::Title => vec![self.fields.title],
+                Scope::Tag => vec![self.fields.tags],
+                Scope::Category => vec![self.fields.categories],
+            };
+            let mut field_queries: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+            for field in fields {
+                if let Some(query) = self.atom_query(field, &clause.atom)? {
+                    field_queries.push((Occur::Should, query));
+                }
+            }
+            // 記号のみ等で語句が残らない場合は何にも一致しない
+            let query: Box<dyn Query> = if field_queries.is_empty() {
+                Box::new(EmptyQuery)
+            } else {
+                Box::new(BooleanQuery::new(field_queries))
+            };
+            let occur = match clause.occurrence {
+                Occurrence::Should => Occur::Should,
+                Occurrence::Must => Occur::Must,
+                Occurrence::MustNot => Occur::MustNot,
+            };
+            queries.push((occur, query));
+        }
+
+        // 除外する語句だけの場合は、それ以外のすべての記事を対象にする
+        if queries.iter().all(|(occur, _)| *occur == Occur::MustNot) {
+            queries.push((Occur::Must, Box::new(AllQuery)));
+        }
+        Ok(Box::new(BooleanQuery::new(queries)))
+    }
+
+    // 語句をフィールドのトークナイザー (title 等は lang_ja) で分割して検索条件にする
+    fn atom_query(&self, field: Field, atom: &Atom) -> Result<Option<Box<dyn Query>>, BoxError> {
+        let text = match atom {
+            Atom::Word(text) | Atom::Phrase(text) | Atom::Prefix(text) | Atom::Fuzzy(text, _) => {
+                text
+            }
+        };
+        let mut tokenizer = self.index.tokenizer_for_field(field)?;
+        let mut terms = Vec::new();
+        tokenizer.token_stream(text).process(&mut |token| {
+            terms.push((token.position, Term::from_field_text(field, &token.text)));
+        });
+        if terms.is_empty() {
+            return Ok(None);
+        }
+
+        let record_option = self
+            .index
+            .schema()
+            .get_field_entry(field)
+            .field_type()
+            .get_index_record_option()
+            .unwrap_or(IndexRecordOption::Basic);
+        let query: Box<dyn Query> = match atom {
+            Atom::Word(_) | Atom::Phrase(_) if terms.len() == 1 => {
+                Box::new(TermQuery::new(terms.remove(0).1, record_option))
+            }
+            Atom::Word(_) | Atom::Phrase(_) => Box::new(PhraseQuery::new_with_offset(terms)),
+            Atom::Prefix(_) if terms.len() == 1 => {
+                Box::new(FuzzyTermQuery::new_prefix(terms.remove(0).1, 0, true))
+            }
+            Atom::Prefix(_) => Box::new(PhrasePrefixQuery::new_with_offset(terms)),
+            Atom::Fuzzy(_, distance) => Box::new(BooleanQuery::new(
+                terms
+                    .into_iter()
+                    .map(|(_, term)| {
+                        let query: Box<dyn Query> =
+                            Box::new(FuzzyTermQuery::new(term, *distance, true));
+                        (Occur::Must, query)
+                    })
+                    .collect(),
+            )),
+        };
+        Ok(Some(query))
+    }
+
     async fn count_query(&self, query: BooleanQuery) -> Result<usize, BoxError> {
         let index = self.index.clone();
 
@@ -279,31 +648,35 @@ impl SearchEngineRepositoryImpl {
         .await?
     }
 
+    // 条件に合う記事のタグ・カテゴリーを数える (件数の多い順、同数の場合は名前順)
     async fn calculate_freq_map(
         &self,
-        field: Field,
+        column: &'static str,
         limit: usize,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
     ) -> Result<Vec<(String, u64)>, BoxError> {
+        let filter = self.build_query(&SearchParams {
+            word: None,
+            draft,
+            date_from: None,
+            date_to,
+            tags: None,
+            categories: None,
+            page: None,
+            per_page: None,
+            sort: None,
+            advanced: false,
+            facets: false,
+        })?;
         let index = self.index.clone();
 
         tokio::task::spawn_blocking(move || -> Result<Vec<(String, u64)>, BoxError> {
             let reader = index.reader()?;
             let searcher = reader.searcher();
-            let mut freq_map = std::collections::HashMap::new();
-
-            for segment_reader in searcher.segment_readers() {
-                let inverted_index = segment_reader.inverted_index(field)?;
-                let term_dict = inverted_index.terms();
-                let mut stream = term_dict.stream()?;
-
-                while let Some((term_bytes, term_info)) = stream.next() {
-                    let term_str = std::str::from_utf8(term_bytes)?.to_string();
-                    *freq_map.entry(term_str).or_insert(0) += term_info.doc_freq as u64;
-                }
-            }
 
-            let mut result: Vec<(String, u64)> = freq_map.into_iter().collect();
-            result.sort_by(|a, b| b.1.cmp(&a.1));
+            let mut result = count_column_terms(&searcher, &filter, column)?;
+            result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
             result.truncate(limit);
 
             Ok(result)
@@ -314,6 +687,7 @@ impl SearchEngineRepositoryImpl {
     async fn fetch_documents(
         &self,
         query: &BooleanQuery,
+        sort: SearchSort,
         start: usize,
         per_page: usize,
         total_count: usize,
@@ -332,16 +706,40 @@ impl SearchEngineRepositoryImpl {
             let reader = index.reader()?;
             let searcher = reader.searcher();
 
-            let collector = TopDocs::with_limit(per_page)
-                .and_offset(start)
-                .order_by_fast_field("date", Order::Desc);
-
-            let top_docs: Vec<(tantivy::DateTime, DocAddress)> =
-                searcher.search(&query_cloned, &collector)?;
+            let top_docs = TopDocs::with_limit(per_page).and_offset(start);
+            let doc_addresses: Vec<DocAddress> = match sort {
+                SearchSort::Relevance => searcher
+                    .search(&query_cloned, &top_docs)?
+                    .into_iter()
+                    .map(|(_score, doc_address)| doc_address)
+                    .collect(),
+                SearchSort::DateDesc | SearchSort::DateAsc => {
+                    let order = if sort == SearchSort::DateAsc {
+                        Order::Asc
+                    } else {
+                        Order::Desc
+                    };
+                    let collector =
+                        top_docs.order_by_fast_field::<tantivy::DateTime>("date", order);
+                    searcher
+                        .search(&query_cloned, &collector)?
+                        .into_iter()
+                        .map(|(_date, doc_address)| doc_address)
+                        .collect()
+                }
+                SearchSort::Title => {
+                    let collector = top_docs.order_by_string_fast_field("title_sort", Order::Asc);
+                    searcher
+                        .search(&query_cloned, &collector)?
+                        .into_iter()
+                        .map(|(_title, doc_address)| doc_address)
+                        .collect()
+                }
+            };
 
-            let results = top_docs
+            let results = doc_addresses
                 .into_iter()
-                .map(|(_sort_value, doc_address)| {
+                .map(|doc_address| {
                     let doc = searcher.doc(doc_address)?;
                     Ok(doc_to_entity(&doc, &fields))
                 })
@@ -354,26 +752,178 @@ impl SearchEngineRepositoryImpl {
         Ok(results)
     }
 
-    async fn register(&self, params: &ContentEntity) -> Result<ContentEntity, BoxError> {
+    // 検索語の一致箇所を highlight_prefix / highlight_postfix で囲んだタイトル・本文の抜粋
+    // 語句の区切りはインデックスと同じ lang_ja で判定し、抜粋の文字列は HTML エスケープする
+    async fn highlight(
+        &self,
+        query: &BooleanQuery,
+        contents: &[ContentEntity],
+    ) -> Result<Vec<SearchHighlight>, BoxError> {
+        let index = self.index.clone();
+        let fields = self.fields.clone();
+        let query = query.clone();
+        let contents = contents.to_vec();
+
+        tokio::task::spawn_blocking(move || -> Result<Vec<SearchHighlight>, BoxError> {
+            let reader = index.reader()?;
+            let searcher = reader.searcher();
+
+            let mut title_generator = SnippetGenerator::create(&searcher, &query, fields.title)?;
+            title_generator.set_max_num_chars(CONFIG.content.title_max_len);
+            let mut body_generator = SnippetGenerator::create(&searcher, &query, fields.body)?;
+            body_generator.set_max_num_chars(CONFIG.search.snippet_max_chars);
+
+            let snippet = |generator: &SnippetGenerator, text: &str| {
+                let mut snippet = generator.snippet(text);
+                if snippet.is_empty() {
+                    return None;
+                }
+                snippet.set_snippet_prefix_postfix(
+                    &CONFIG.search.highlight_prefix,
+                    &CONFIG.search.highlight_postfix,
+                );
+                Some(snippet.to_html())
+            };
+
+            Ok(contents
+                .iter()
+                .map(|content| SearchHighlight {
+                    id: content.id.clone(),
+                    title: snippet(&title_generator, &content.matter.title),
+                    body: snippet(&body_generator, &content.body),
+                })
+                .collect())
+        })
+        .await?
+    }
+
+    // 検索条件に一致するすべての記事の、タグ・カテゴリー・下書き・年月ごとの件数
+    async fn facets(&self, query: &BooleanQuery) -> Result<SearchFacets, BoxError> {
+        let index = self.index.clone();
+        let query = query.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<SearchFacets, BoxError> {
+            let reader = index.reader()?;
+            let searcher = reader.searcher();
+            let doc_addresses = searcher.search(&query, &DocSetCollector)?;
+
+            let mut segments = std::collections::BTreeMap::new();
+            for doc_address in doc_addresses {
+                segments
+                    .entry(doc_address.segment_ord)
+                    .or_insert_with(Vec::new)
+                    .push(doc_address.doc_id);
+            }
+
+            let mut tags = std::collections::HashMap::new();
+            let mut categories = std::collections::HashMap::new();
+            let mut draft = std::collections::BTreeMap::new();
+            let mut months = std::collections::BTreeMap::new();
+
+            for (segment_ord, doc_ids) in segments {
+                let fast_fields = searcher.segment_reader(segment_ord).fast_fields();
+                let tag_column = fast_fields.str("tags")?;
+                let category_column = fast_fields.str("categories")?;
+                let draft_column = fast_fields.bool("draft")?;
+                let date_column = fast_fields.date("date")?;
+
+                // 語句はセグメントごとの番号で数えてから文字列にする
+                let mut tag_ords = std::collections::HashMap::new();
+                let mut category_ords = std::collections::HashMap::new();
+                for doc_id in doc_ids {
+                    if let Some(column) = &tag_column {
+                        for ord in column.term_ords(doc_id) {
+                            *tag_ords.entry(ord).or_insert(0u64) += 1;
+                        }
+                    }
+                    if let Some(column) = &category_column {
+                        for ord in column.term_ords(doc_id) {
+                            *category_ords.entry(ord).or_insert(0u64) += 1;
+                        }
+                    }
+                    if let Some(value) = draft_column.first(doc_id) {
+                        *draft.entry(value).or_insert(0u64) += 1;
+                    }
+                    if let Some(date) = date_column.first(doc_id) {
+                        let date = chrono::Utc
+                            .timestamp_opt(date.into_timestamp_secs(), 0)
+                            .unwrap();
+                        *months.entry((date.year(), date.month())).or_insert(0u64) += 1;
+                    }
+                }
+
+                let ords = [
+                    (&tag_column, tag_ords, &mut tags),
+                    (&category_column, category_ords, &mut categories),
+                ];
+                for (column, ords, counts) in ords {
+                    let Some(column) = column else {
+                        continue;
+                    };
+                    for (ord, count) in ords {
+                        let mut term = String::new();
+                        if column.ord_to_str(ord, &mut term)? {
+                            *counts.entry(term).or_insert(0u64) += count;
+                        }
+                    }
+                }
+            }
+
+            // 件数の多い順 (同数の場合は名前順)
+            let sorted = |counts: std::collections::HashMap<String, u64>| {
+                let mut counts = counts.into_iter().collect::<Vec<_>>();
+                counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                counts
+            };
+
+            Ok(SearchFacets {
+                tags: sorted(tags),
+                categories: sorted(categories),
+                draft: draft.into_iter().collect(),
+                // 新しい年月順
+                months: months
+                    .into_iter()
+                    .rev()
+                    .map(|((year, month), count)| MonthCount { year, month, count })
+                    .collect(),
+            })
+        })
+        .await?
+    }
+
+    fn to_document(fields: &SchemaFields, params: &ContentEntity) -> TantivyDocument {
         let mut doc = doc!(
-            self.fields.id => params.id.to_string(),
-            self.fields.title => params.matter.title,
-            self.fields.description => params.matter.description.clone().unwrap_or_default(),
-            self.fields.body => params.body,
-            self.fields.date => tantivy::DateTime::from_timestamp_secs(params.matter.date.timestamp()),
-            self.fields.draft => params.matter.draft,
+            fields.id => params.id.to_string(),
+            fields.title => params.matter.title.clone(),
+            fields.title_sort => params.matter.title.clone(),
+            fields.description => params.matter.description.clone().unwrap_or_default(),
+            fields.body => params.body.clone(),
+            fields.date => tantivy::DateTime::from_timestamp_secs(params.matter.date.timestamp()),
+            fields.draft => params.matter.draft,
         );
         for tag in &params.matter.tags {
-            doc.add_text(self.fields.tags, tag);
+            doc.add_text(fields.tags, tag);
         }
         for category in &params.matter.categories {
-            doc.add_text(self.fields.categories, category);
+            doc.add_text(fields.categories, category);
+        }
+        if let Some(slug) = &params.matter.slug {
+            doc.add_text(fields.slug, slug);
         }
+        doc
+    }
+
+    async fn register(&self, params: &ContentEntity) -> Result<ContentEntity, BoxError> {
+        let doc = Self::to_document(&self.fields, params);
         self.writer_handle.add_document(doc).await?;
 
         Ok(params.clone())
     }
 
+    pub(crate) fn writer_handle(&self) -> IndexWriterHandle {
+        self.writer_handle.clone()
+    }
+
     async fn check_index_limit(&self) -> Result<(), BoxError> {
         let index = self.index.clone();
 
@@ -421,11 +971,18 @@ impl SearchEngineRepository for SearchEngineRepositoryImpl {
         if count == 0 {
             return Ok(0);
         }
-        let id_term = Term::from_field_text(self.fields.id, &id);
+        let id_term = Term::from_field_text(self.fields.id, id);
         self.writer_handle.delete_term(id_term).await?;
         Ok(count)
     }
 
+    // find で取得したドキュメント (タグ除去済み) をそのまま登録し直す
+    async fn restore(&self, params: &ContentEntity) -> Result<ContentEntity, BoxError> {
+        tracing::debug!("restore id: {}", params.id);
+        self.remove(&params.id).await?;
+        self.register(params).await
+    }
+
     async fn edit(&self, params: &ContentEntity) -> Result<Option<ContentEntity>, BoxError> {
         tracing::debug!("edit id: {}", params.id);
         if self.find(&params.id).await?.is_none() {
@@ -445,6 +1002,11 @@ impl SearchEngineRepository for SearchEngineRepositoryImpl {
         Ok(Some(params.clone()))
     }
 
+    async fn clear(&self) -> Result<(), BoxError> {
+        tracing::debug!("clear all documents");
+        self.writer_handle.delete_all().await
+    }
+
     async fn commit(&self) -> Result<(), BoxError> {
         self.writer_handle.commit().await
     }
@@ -482,6 +1044,34 @@ impl SearchEngineRepository for SearchEngineRepositoryImpl {
         .await?
     }
 
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<ContentEntity>, BoxError> {
+        let slug = slug.to_string();
+        let fields = self.fields.clone();
+        let index = self.index.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<Option<ContentEntity>, BoxError> {
+            let reader = index.reader()?;
+            let searcher = reader.searcher();
+
+            let term = Term::from_field_text(fields.slug, &slug);
+            let query = TermQuery::new(term, IndexRecordOption::Basic);
+
+            // slug は一意になるように登録しているが、重複していた場合は日付の新しいものを返す
+            let collector = TopDocs::with_limit(1).order_by_fast_field("date", Order::Desc);
+            let top_docs: Vec<(tantivy::DateTime, DocAddress)> =
+                searcher.search(&query, &collector)?;
+
+            match top_docs.first() {
+                Some((_, doc_address)) => {
+                    let doc = searcher.doc(*doc_address)?;
+                    Ok(Some(doc_to_entity(&doc, &fields)))
+                }
+                None => Ok(None),
+            }
+        })
+        .await?
+    }
+
     async fn count(&self, id: &str) -> Result<usize, BoxError> {
         let index = self.index.clone();
         let fields = self.fields.clone();
@@ -506,9 +1096,10 @@ impl SearchEngineRepository for SearchEngineRepositoryImpl {
     async fn search(&self, params: &SearchParams) -> Result<SearchResult, BoxError> {
         tracing::debug!("search params: {:?}", params);
 
-        let query = self.build_query(&params)?;
+        let query = self.build_query(params)?;
 
         let mut total_count = self.count_query(query.clone()).await?;
+        let total = total_count;
 
         tracing::debug!("total count: {}", total_count);
 
@@ -523,29 +1114,428 @@ impl SearchEngineRepository for SearchEngineRepositoryImpl {
             paginate(total_count, params.page, params.per_page);
 
         let contents = self
-            .fetch_documents(&query, start, per_page as usize, total_count as usize)
+            .fetch_documents(
+                &query,
+                sort_order(params),
+                start,
+                per_page,
+                total_count as usize,
+            )
             .await?;
 
         // tracing::debug!("contents: {:#?}", contents);
 
+        let highlights = if params.word.is_some() {
+            self.highlight(&query, &contents).await?
+        } else {
+            Vec::new()
+        };
+
+        let facets = if params.facets {
+            self.facets(&query).await?
+        } else {
+            SearchFacets::default()
+        };
+
         Ok(SearchResult {
             page,
             per_page,
             max_page,
             overflow,
+            total,
             contents,
+            highlights,
+            facets,
+        })
+    }
+
+    async fn search_all(&self, params: &SearchParams) -> Result<Vec<ContentEntity>, BoxError> {
+        tracing::debug!("search all params: {:?}", params);
+
+        // search_limit / ページングの制限なしで全件取得する
+        let query = self.build_query(params)?;
+        let total_count = self.count_query(query.clone()).await?;
+
+        self.fetch_documents(&query, sort_order(params), 0, total_count, total_count)
+            .await
+    }
+
+    // タグ・カテゴリーの一致と、タイトル・本文を lang_ja で分かち書きした語句の重なりでスコアを付ける
+    // 対象は公開中の記事のみ (id の記事自身は除く)
+    async fn related(&self, id: &str, limit: usize) -> Result<Vec<ContentEntity>, BoxError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let Some(entity) = self.find(id).await? else {
+            return Ok(Vec::new());
+        };
+
+        let published = self.build_query(&SearchParams {
+            word: None,
+            draft: Some(false),
+            date_from: None,
+            date_to: Some(Utc::now()),
+            tags: None,
+            categories: None,
+            page: None,
+            per_page: None,
+            sort: None,
+            advanced: false,
+            facets: false,
+        })?;
+
+        let index = self.index.clone();
+        let fields = self.fields.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<Vec<ContentEntity>, BoxError> {
+            let reader = index.reader()?;
+            let searcher = reader.searcher();
+
+            let mut similar: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+            let terms = entity
+                .matter
+                .tags
+                .iter()
+                .map(|tag| (fields.tags, tag, RELATED_TAG_BOOST))
+                .chain(
+                    entity
+                        .matter
+                        .categories
+                        .iter()
+                        .map(|category| (fields.categories, category, RELATED_CATEGORY_BOOST)),
+                );
+            for (field, text, boost) in terms {
+                let term = Term::from_field_text(field, text);
+                let query = TermQuery::new(term, IndexRecordOption::Basic);
+                similar.push((
+                    Occur::Should,
+                    Box::new(BoostQuery::new(Box::new(query), boost)),
+                ));
+            }
+
+            // 自身にしか出現しない語句は手がかりにならないため、2件以上の記事に出現するものを使う
+            let more_like_this = MoreLikeThisQuery::builder()
+                .with_min_doc_frequency(2)
+                .with_min_term_frequency(1)
+                .with_max_query_terms(RELATED_MAX_TERMS)
+                .with_document_fields(vec![
+                    (
+                        fields.title,
+                        vec![OwnedValue::Str(entity.matter.title.clone())],
+                    ),
+                    (fields.body, vec![OwnedValue::Str(entity.body.clone())]),
+                ]);
+            similar.push((Occur::Should, Box::new(more_like_this)));
+
+            let self_term = Term::from_field_text(fields.id, &entity.id);
+            let query = BooleanQuery::new(vec![
+                (Occur::Must, Box::new(BooleanQuery::new(similar))),
+                // 公開条件はスコアに影響させない
+                (
+                    Occur::Must,
+                    Box::new(ConstScoreQuery::new(Box::new(published), 0.0)),
+                ),
+                (
+                    Occur::MustNot,
+                    Box::new(TermQuery::new(self_term, IndexRecordOption::Basic)),
+                ),
+            ]);
+
+            let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+            top_docs
+                .into_iter()
+                .map(|(_score, doc_address)| {
+                    let doc = searcher.doc(doc_address)?;
+                    Ok(doc_to_entity(&doc, &fields))
+                })
+                .collect()
+        })
+        .await?
+    }
+
+    // 本文と同じ lang_ja で分かち書きし、記号のみの語句を除いて重複なく昇順に並べる
+    // 英字はブラウザ側で大文字・小文字を区別せずに照合できるよう小文字にする
+    async fn terms(&self, texts: &[String]) -> Result<Vec<Vec<String>>, BoxError> {
+        let mut tokenizer = self.index.tokenizer_for_field(self.fields.body)?;
+        let texts = texts.to_vec();
+
+        tokio::task::spawn_blocking(move || -> Result<Vec<Vec<String>>, BoxError> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mut terms = std::collections::BTreeSet::new();
+                    tokenizer.token_stream(text).process(&mut |token| {
+                        if token.text.chars().any(char::is_alphanumeric) {
+                            terms.insert(token.text.to_lowercase());
+                        }
+                    });
+                    terms.into_iter().collect()
+                })
+                .collect())
         })
+        .await?
     }
 
-    async fn top_tags(&self, limit: usize) -> Result<Vec<(String, u64)>, BoxError> {
-        Ok(self.calculate_freq_map(self.fields.tags, limit).await?)
+    async fn top_tags(
+        &self,
+        limit: usize,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, u64)>, BoxError> {
+        self.calculate_freq_map("tags", limit, draft, date_to).await
     }
 
-    async fn top_categories(&self, limit: usize) -> Result<Vec<(String, u64)>, BoxError> {
-        Ok(self
-            .calculate_freq_map(self.fields.categories, limit)
-            .await?)
+    async fn top_categories(
+        &self,
+        limit: usize,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, u64)>, BoxError> {
+        self.calculate_freq_map("categories", limit, draft, date_to)
+            .await
     }
+
+    async fn date_histogram(
+        &self,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<MonthCount>, BoxError> {
+        let index = self.index.clone();
+        let query = self.build_query(&SearchParams {
+            word: None,
+            draft,
+            date_from: None,
+            date_to,
+            tags: None,
+            categories: None,
+            page: None,
+            per_page: None,
+            sort: None,
+            advanced: false,
+            facets: false,
+        })?;
+
+        tokio::task::spawn_blocking(move || -> Result<Vec<MonthCount>, BoxError> {
+            let reader = index.reader()?;
+            let searcher = reader.searcher();
+            let doc_addresses = searcher.search(&query, &DocSetCollector)?;
+
+            let mut columns = std::collections::HashMap::new();
+            let mut histogram = std::collections::BTreeMap::new();
+
+            for doc_address in doc_addresses {
+                let column = match columns.entry(doc_address.segment_ord) {
+                    std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                    std::collections::hash_map::Entry::Vacant(entry) => entry.insert(
+                        searcher
+                            .segment_reader(doc_address.segment_ord)
+                            .fast_fields()
+                            .date("date")?,
+                    ),
+                };
+                let Some(date) = column.first(doc_address.doc_id) else {
+                    continue;
+                };
+                let date = chrono::Utc
+                    .timestamp_opt(date.into_timestamp_secs(), 0)
+                    .unwrap();
+                *histogram.entry((date.year(), date.month())).or_insert(0u64) += 1;
+            }
+
+            // 新しい年月順
+            Ok(histogram
+                .into_iter()
+                .rev()
+                .map(|((year, month), count)| MonthCount { year, month, count })
+                .collect())
+        })
+        .await?
+    }
+
+    // 語句の辞書から prefix で始まる語句を取り出し、条件に一致する記事数の多い順に返す
+    async fn suggest(
+        &self,
+        field: SuggestField,
+        prefix: &str,
+        limit: usize,
+        draft: Option<bool>,
+        date_to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, u64)>, BoxError> {
+        let prefix = prefix.trim().to_string();
+        if prefix.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        // タイトルは lang_ja で分かち書きした最後の語句を小文字にして前方一致させる
+        let (field, prefix) = match field {
+            SuggestField::Tags => (self.fields.tags, prefix),
+            SuggestField::Categories => (self.fields.categories, prefix),
+            SuggestField::Title => {
+                let mut tokenizer = self.index.tokenizer_for_field(self.fields.title)?;
+                let mut last = None;
+                tokenizer.token_stream(&prefix).process(&mut |token| {
+                    last = Some(token.text.to_lowercase());
+                });
+                match last {
+                    Some(last) => (self.fields.title, last),
+                    None => return Ok(Vec::new()),
+                }
+            }
+        };
+        let filter = self.build_query(&SearchParams {
+            word: None,
+            draft,
+            date_from: None,
+            date_to,
+            tags: None,
+            categories: None,
+            page: None,
+            per_page: None,
+            sort: None,
+            advanced: false,
+            facets: false,
+        })?;
+        let index = self.index.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<Vec<(String, u64)>, BoxError> {
+            let reader = index.reader()?;
+            let searcher = reader.searcher();
+
+            let mut counts = suggest_from_terms(
+                &searcher,
+                &filter,
+                field,
+                &prefix,
+                limit * SUGGEST_CANDIDATE_FACTOR,
+            )?;
+
+            counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            counts.truncate(limit);
+            Ok(counts)
+        })
+        .await?
+    }
+}
+
+// 条件に合う記事を一度だけ集め、fast field の語句を番号ごとに数える
+fn count_column_terms(
+    searcher: &Searcher,
+    filter: &BooleanQuery,
+    column: &str,
+) -> Result<Vec<(String, u64)>, BoxError> {
+    let doc_addresses = searcher.search(filter, &DocSetCollector)?;
+
+    let mut segments = std::collections::BTreeMap::new();
+    for doc_address in doc_addresses {
+        segments
+            .entry(doc_address.segment_ord)
+            .or_insert_with(Vec::new)
+            .push(doc_address.doc_id);
+    }
+
+    let mut counts = std::collections::HashMap::new();
+    for (segment_ord, doc_ids) in segments {
+        let fast_fields = searcher.segment_reader(segment_ord).fast_fields();
+        let Some(column) = fast_fields.str(column)? else {
+            continue;
+        };
+        let mut ords = std::collections::HashMap::new();
+        for doc_id in doc_ids {
+            for ord in column.term_ords(doc_id) {
+                *ords.entry(ord).or_insert(0u64) += 1;
+            }
+        }
+        for (ord, count) in ords {
+            let mut term = String::new();
+            if column.ord_to_str(ord, &mut term)? {
+                *counts.entry(term).or_insert(0u64) += count;
+            }
+        }
+    }
+    Ok(counts.into_iter().collect())
+}
+
+// 前方一致する語句から辞書の件数 (削除済みや条件外の記事を含む) の多い順に max_candidates 件を選び、
+// 条件に合う記事の数を数え直す
+// 辞書は大文字・小文字を区別して並ぶため、prefix の小文字・大文字・先頭のみ大文字の範囲を順に読む
+fn suggest_from_terms(
+    searcher: &Searcher,
+    filter: &BooleanQuery,
+    field: Field,
+    prefix: &str,
+    max_candidates: usize,
+) -> Result<Vec<(String, u64)>, BoxError> {
+    let prefixes = case_variants(prefix);
+    let mut doc_freqs = std::collections::HashMap::new();
+    for segment_reader in searcher.segment_readers() {
+        let inverted_index = segment_reader.inverted_index(field)?;
+        // セグメントごとに件数の多い max_candidates 件だけを残す
+        let mut top = std::collections::BinaryHeap::new();
+        for prefix in &prefixes {
+            let mut stream = inverted_index
+                .terms()
+                .range()
+                .ge(prefix.as_bytes())
+                .into_stream()?;
+            while stream.advance() {
+                let key = stream.key();
+                if !key.starts_with(prefix.as_bytes()) {
+                    break;
+                }
+                let Ok(term) = std::str::from_utf8(key) else {
+                    continue;
+                };
+                top.push(std::cmp::Reverse((
+                    stream.value().doc_freq,
+                    term.to_string(),
+                )));
+                if top.len() > max_candidates {
+                    top.pop();
+                }
+            }
+        }
+        for std::cmp::Reverse((doc_freq, term)) in top {
+            *doc_freqs.entry(term).or_insert(0u32) += doc_freq;
+        }
+    }
+
+    let mut candidates = doc_freqs.into_iter().collect::<Vec<_>>();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    candidates.truncate(max_candidates);
+
+    let mut counts = Vec::new();
+    for (term, _) in candidates {
+        let term_query = TermQuery::new(
+            Term::from_field_text(field, &term),
+            IndexRecordOption::Basic,
+        );
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, Box::new(term_query) as Box<dyn Query>),
+            (Occur::Must, Box::new(filter.clone())),
+        ]);
+        let count = searcher.search(&query, &Count)?;
+        if count > 0 {
+            counts.push((term, count as u64));
+        }
+    }
+    Ok(counts)
+}
+
+// rust → rust, RUST, Rust (重複は除く)
+fn case_variants(prefix: &str) -> Vec<String> {
+    let lower = prefix.to_lowercase();
+    let mut chars = lower.chars();
+    let capitalized = chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+        .unwrap_or_default();
+    let mut variants = vec![prefix.to_string()];
+    for variant in [lower.clone(), lower.to_uppercase(), capitalized] {
+        if !variants.contains(&variant) {
+            variants.push(variant);
+        }
+    }
+    variants
 }
 
 // helper
@@ -560,11 +1550,20 @@ fn doc_to_entity(doc: &TantivyDocument, fields: &SchemaFields) -> ContentEntity
             draft: get_bool(doc, fields.draft),
             tags: get_str_list(doc, fields.tags),
             categories: get_str_list(doc, fields.categories),
+            slug: Some(get_str(doc, fields.slug)).filter(|slug| !slug.is_empty()),
         },
         body: get_str(doc, fields.body),
     }
 }
 
+// 検索語が無い場合の関連度はすべて同じため、日付の新しい順にする
+fn sort_order(params: &SearchParams) -> SearchSort {
+    match params.sort.unwrap_or_default() {
+        SearchSort::Relevance if params.word.is_none() => SearchSort::DateDesc,
+        sort => sort,
+    }
+}
+
 fn get_str(doc: &TantivyDocument, field: Field) -> String {
     doc.get_first(field)
         .and_then(|v| v.as_str().map(|s| s.to_string()))
@@ -609,15 +1608,15 @@ fn paginate(
     let pages = if total == 0 {
         0
     } else {
-        (total + per_page - 1) / per_page
+        total.div_ceil(per_page)
     };
 
     if pages > 0 && page > pages {
         page = pages;
     }
 
-    let start = ((page - 1) * per_page).min(total) as usize;
-    let end = ((start + per_page).min(total)) as usize;
+    let start = ((page - 1) * per_page).min(total);
+    let end = (start + per_page).min(total);
 
     tracing::debug!(
         "paginate exit: {}, end: {}, page: {}, per_page: {}, pages: {}",
@@ -649,7 +1648,7 @@ fn recursive_strip_tags(element: &ElementRef, plain_text: &mut String) {
         if let Some(text) = child.value().as_text() {
             plain_text.push_str(text);
             plain_text.push(' ');
-        } else if let Some(ce) = ElementRef::wrap(child.clone()) {
+        } else if let Some(ce) = ElementRef::wrap(child) {
             recursive_strip_tags(&ce, plain_text);
         }
     }
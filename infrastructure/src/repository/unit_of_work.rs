@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::repository::{index_writer_handle::IndexWriterHandle, journal::FileJournal};
+use common::types::BoxError;
+use domain::repository::unit_of_work::UnitOfWorkRepository;
+
+pub struct UnitOfWorkRepositoryImpl {
+    journal: Arc<FileJournal>,
+    writer_handle: IndexWriterHandle,
+}
+
+impl UnitOfWorkRepositoryImpl {
+    pub(crate) fn new(journal: Arc<FileJournal>, writer_handle: IndexWriterHandle) -> Self {
+        Self {
+            journal,
+            writer_handle,
+        }
+    }
+}
+
+#[async_trait]
+impl UnitOfWorkRepository for UnitOfWorkRepositoryImpl {
+    async fn begin(&self) -> Result<(), BoxError> {
+        let id = self.journal.begin().await?;
+        tracing::debug!("begin unit of work: {}", id);
+        Ok(())
+    }
+
+    // 作業単位の id を payload にして検索インデックスをコミットする
+    // 以降に停止した場合、起動時の復旧ではファイルの変更も確定させる
+    async fn commit_index(&self) -> Result<(), BoxError> {
+        let id = self
+            .journal
+            .id()
+            .await
+            .ok_or_else(|| BoxError::from("no unit of work in progress"))?;
+        self.writer_handle.commit_with_payload(&id).await
+    }
+
+    async fn commit(&self) -> Result<(), BoxError> {
+        self.journal.commit().await
+    }
+
+    // コミット前の検索インデックスの変更とファイルの変更を取り消す
+    async fn rollback(&self) -> Result<(), BoxError> {
+        let index = self.writer_handle.rollback().await;
+        let files = self.journal.rollback().await;
+        index.and(files)
+    }
+}
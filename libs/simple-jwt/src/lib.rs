@@ -25,11 +25,11 @@ impl Claims {
 }
 
 pub fn encode(claims: &Claims, secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
-    Ok(jsonwebtoken::encode(
+    jsonwebtoken::encode(
         &Header::default(),
         &claims,
         &EncodingKey::from_secret(secret.to_string().as_bytes()),
-    )?)
+    )
 }
 
 pub fn decode(
@@ -47,7 +47,7 @@ pub fn decode(
     }
     validation.set_issuer(&[issuer]);
     let claims: Claims = jsonwebtoken::decode::<Claims>(
-        &token,
+        token,
         &DecodingKey::from_secret(secret.to_string().as_ref()),
         &validation,
     )?
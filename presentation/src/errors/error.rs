@@ -1,6 +1,6 @@
 use application::AppError;
 use axum::Json;
-use axum::http::StatusCode;
+use axum::http::{HeaderValue, StatusCode, header};
 use axum::response::{IntoResponse, Response};
 use serde_json::json;
 
@@ -8,6 +8,8 @@ pub struct ApiError(AppError);
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
+        // 現在のバージョンは ETag ヘッダーで返す
+        let mut etag = None;
         let (status, error_message) = match self.0 {
             AppError::BadRequest(reason) => (StatusCode::BAD_REQUEST, reason),
             AppError::Unauthorized() => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string()),
@@ -15,6 +17,13 @@ impl IntoResponse for ApiError {
             AppError::ServerBusy() => (StatusCode::SERVICE_UNAVAILABLE, "Server busy".to_string()),
             AppError::Inconsistent(reason) => (StatusCode::CONFLICT, reason),
             AppError::AccountLocked() => (StatusCode::UNAUTHORIZED, "Account locked".to_string()),
+            AppError::Aborted(stage, ex) => {
+                tracing::error!("Aborted at stage '{}': {:?}", stage, ex);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed at stage '{}'. All changes were rolled back.", stage),
+                )
+            }
             AppError::Unexpected(ex) => {
                 tracing::error!("Internal server error: {:?}", ex);
                 (
@@ -24,10 +33,19 @@ impl IntoResponse for ApiError {
             }
             AppError::DataNotFound(reason) => (StatusCode::NOT_FOUND, reason),
             AppError::DataConflict(reason) => (StatusCode::CONFLICT, reason),
+            AppError::VersionMismatch(version) => {
+                let message = format!("version mismatch: current version is \"{}\"", version);
+                etag = HeaderValue::from_str(&format!("\"{}\"", version)).ok();
+                (StatusCode::CONFLICT, message)
+            }
         };
 
         let body = Json(json!({ "error": error_message }));
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(value) = etag {
+            response.headers_mut().insert(header::ETAG, value);
+        }
+        response
     }
 }
 
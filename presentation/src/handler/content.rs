@@ -2,25 +2,34 @@ use crate::errors::error::ApiError;
 use crate::middleware::auth::AuthMember;
 
 use application::{
-    UseCaseModule,
+    AppError, UseCaseModule,
     model::{
-        content::ContentDto,
-        search_engine::{SearchQueryDto, SearchRequestDto, SearchResponseDto},
+        bulk::{BulkReportDto, BulkRequestDto, MergeTermsDto},
+        content::{CheckReportDto, ContentDto, IfMatch},
+        revision::{RevisionDiffDto, RevisionDiffQueryDto, RevisionDto},
+        search_engine::{
+            ArchiveCountDto, SearchContentDto, SearchQueryDto, SearchRequestDto, SearchResponseDto,
+            SuggestQueryDto,
+        },
     },
 };
 use axum::{
     Extension, Json,
     extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, header},
 };
 use std::sync::Arc;
 
 pub async fn create(
     State(usecases): State<Arc<dyn UseCaseModule>>,
-    Extension(_member): Extension<AuthMember>,
+    Extension(member): Extension<AuthMember>,
     Json(dto): Json<ContentDto>,
-) -> Result<Json<ContentDto>, ApiError> {
-    let res = usecases.content().create(&dto).await?;
-    Ok(Json(res))
+) -> Result<(HeaderMap, Json<ContentDto>), ApiError> {
+    let res = usecases
+        .content()
+        .create(&dto, &member.member.account)
+        .await?;
+    Ok((etag(Some(&res)), Json(res)))
 }
 
 pub async fn search(
@@ -45,26 +54,54 @@ pub async fn find(
     State(usecases): State<Arc<dyn UseCaseModule>>,
     Extension(_member): Extension<AuthMember>,
     Path(id): Path<String>,
-) -> Result<Json<Option<ContentDto>>, ApiError> {
+) -> Result<(HeaderMap, Json<Option<ContentDto>>), ApiError> {
     let res = usecases.content().find(&id).await?;
-    Ok(Json(res))
+    Ok((etag(res.as_ref()), Json(res)))
+}
+
+pub async fn find_by_slug(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    Path(slug): Path<String>,
+) -> Result<(HeaderMap, Json<Option<ContentDto>>), ApiError> {
+    let res = usecases.content().find_by_slug(&slug).await?;
+    Ok((etag(res.as_ref()), Json(res)))
 }
 
 pub async fn remove(
     State(usecases): State<Arc<dyn UseCaseModule>>,
     Extension(_member): Extension<AuthMember>,
     Path(id): Path<String>,
+    headers: HeaderMap,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    let res = usecases.content().remove(&id).await?;
+    let if_match = if_match(&headers)?;
+    let res = usecases.content().remove(&id, if_match.as_ref()).await?;
     Ok(Json(res))
 }
 
 pub async fn edit(
     State(usecases): State<Arc<dyn UseCaseModule>>,
-    Extension(_member): Extension<AuthMember>,
+    Extension(member): Extension<AuthMember>,
+    headers: HeaderMap,
     Json(dto): Json<ContentDto>,
-) -> Result<Json<ContentDto>, ApiError> {
-    let res = usecases.content().edit(&dto).await?;
+) -> Result<(HeaderMap, Json<ContentDto>), ApiError> {
+    let if_match = if_match(&headers)?;
+    let res = usecases
+        .content()
+        .edit(&dto, if_match.as_ref(), &member.member.account)
+        .await?;
+    Ok((etag(Some(&res)), Json(res)))
+}
+
+pub async fn bulk(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(member): Extension<AuthMember>,
+    Json(dto): Json<BulkRequestDto>,
+) -> Result<Json<BulkReportDto>, ApiError> {
+    let res = usecases
+        .content()
+        .bulk(&dto, &member.member.account)
+        .await?;
     Ok(Json(res))
 }
 
@@ -73,7 +110,19 @@ pub async fn tags(
     Extension(_member): Extension<AuthMember>,
     Path(limit): Path<usize>,
 ) -> Result<Json<Vec<(String, u64)>>, ApiError> {
-    let res = usecases.content().tags(limit).await?;
+    let res = usecases.content().tags(limit, None, None).await?;
+    Ok(Json(res))
+}
+
+pub async fn merge_tags(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(member): Extension<AuthMember>,
+    Json(dto): Json<MergeTermsDto>,
+) -> Result<Json<BulkReportDto>, ApiError> {
+    let res = usecases
+        .content()
+        .merge_tags(&dto, &member.member.account)
+        .await?;
     Ok(Json(res))
 }
 
@@ -82,6 +131,129 @@ pub async fn caregories(
     Extension(_member): Extension<AuthMember>,
     Path(limit): Path<usize>,
 ) -> Result<Json<Vec<(String, u64)>>, ApiError> {
-    let res = usecases.content().caregories(limit).await?;
+    let res = usecases.content().caregories(limit, None, None).await?;
+    Ok(Json(res))
+}
+
+pub async fn merge_categories(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(member): Extension<AuthMember>,
+    Json(dto): Json<MergeTermsDto>,
+) -> Result<Json<BulkReportDto>, ApiError> {
+    let res = usecases
+        .content()
+        .merge_categories(&dto, &member.member.account)
+        .await?;
+    Ok(Json(res))
+}
+
+pub async fn archives(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+) -> Result<Json<Vec<ArchiveCountDto>>, ApiError> {
+    let res = usecases.content().archives(None, None).await?;
+    Ok(Json(res))
+}
+
+// 下書きにしか使われていない語句も含む
+pub async fn suggest(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    Query(dto): Query<SuggestQueryDto>,
+) -> Result<Json<Vec<(String, u64)>>, ApiError> {
+    let res = usecases.content().suggest(&dto, None, None).await?;
+    Ok(Json(res))
+}
+
+pub async fn scheduled(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+) -> Result<Json<Vec<SearchContentDto>>, ApiError> {
+    let res = usecases.content().scheduled().await?;
     Ok(Json(res))
 }
+
+// Markdown・HTML・検索インデックスの食い違いを調べる (変更はしない)
+pub async fn check(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+) -> Result<Json<CheckReportDto>, ApiError> {
+    let res = usecases.content().check(false).await?;
+    Ok(Json(res))
+}
+
+pub async fn repair(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+) -> Result<Json<CheckReportDto>, ApiError> {
+    let res = usecases.content().check(true).await?;
+    Ok(Json(res))
+}
+
+pub async fn revisions(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<RevisionDto>>, ApiError> {
+    let res = usecases.content().revisions(&id).await?;
+    Ok(Json(res))
+}
+
+pub async fn revision(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    Path((id, revision)): Path<(String, String)>,
+) -> Result<Json<Option<RevisionDto>>, ApiError> {
+    let res = usecases.content().revision(&id, &revision).await?;
+    Ok(Json(res))
+}
+
+pub async fn diff_revisions(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    Path(id): Path<String>,
+    Query(dto): Query<RevisionDiffQueryDto>,
+) -> Result<Json<RevisionDiffDto>, ApiError> {
+    let res = usecases
+        .content()
+        .diff_revisions(&id, &dto.from, &dto.to)
+        .await?;
+    Ok(Json(res))
+}
+
+pub async fn restore(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(member): Extension<AuthMember>,
+    Path((id, revision)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<(HeaderMap, Json<ContentDto>), ApiError> {
+    let if_match = if_match(&headers)?;
+    let res = usecases
+        .content()
+        .restore(&id, &revision, if_match.as_ref(), &member.member.account)
+        .await?;
+    Ok((etag(Some(&res)), Json(res)))
+}
+
+// If-Match ヘッダー
+fn if_match(headers: &HeaderMap) -> Result<Option<IfMatch>, ApiError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value
+        .to_str()
+        .map_err(|_| AppError::BadRequest("invalid If-Match header".into()))?;
+    Ok(Some(IfMatch::parse(value)))
+}
+
+// 記事のバージョンを ETag ヘッダーにする
+fn etag(dto: Option<&ContentDto>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(value) = dto
+        .and_then(|dto| dto.version.as_ref())
+        .and_then(|version| HeaderValue::from_str(&format!("\"{}\"", version)).ok())
+    {
+        headers.insert(header::ETAG, value);
+    }
+    headers
+}
@@ -0,0 +1,67 @@
+use crate::errors::error::ApiError;
+use crate::middleware::auth::AuthMember;
+
+use application::{
+    AppError, UseCaseModule,
+    model::media::{MediaDto, MediaUploadDto},
+};
+use axum::{
+    Extension, Json,
+    extract::{Multipart, Path, State},
+};
+use std::sync::Arc;
+
+pub async fn upload(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<MediaDto>>, ApiError> {
+    let mut res = Vec::new();
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(e.body_text()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = field.file_name().unwrap_or_default().to_string();
+        let content_type = field.content_type().unwrap_or_default().to_string();
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(e.body_text()))?;
+
+        let dto = MediaUploadDto {
+            file_name,
+            content_type,
+            data: data.to_vec(),
+        };
+        res.push(usecases.media().upload(&dto).await?);
+    }
+
+    if res.is_empty() {
+        return Err(AppError::BadRequest("no file field in request".into()).into());
+    }
+
+    Ok(Json(res))
+}
+
+pub async fn list(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+) -> Result<Json<Vec<MediaDto>>, ApiError> {
+    let res = usecases.media().list().await?;
+    Ok(Json(res))
+}
+
+pub async fn remove(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let res = usecases.media().remove(&name).await?;
+    Ok(Json(res))
+}
@@ -1,3 +1,5 @@
 pub mod auth;
 pub mod content;
+pub mod media;
+pub mod page;
 pub mod public_handler;
@@ -0,0 +1,56 @@
+use crate::errors::error::ApiError;
+use crate::middleware::auth::AuthMember;
+
+use application::{
+    UseCaseModule,
+    model::page::{PageDto, PageSummaryDto},
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use std::sync::Arc;
+
+pub async fn create(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    Json(dto): Json<PageDto>,
+) -> Result<Json<PageDto>, ApiError> {
+    let res = usecases.page().create(&dto).await?;
+    Ok(Json(res))
+}
+
+pub async fn list(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+) -> Result<Json<Vec<PageSummaryDto>>, ApiError> {
+    let res = usecases.page().list(true).await?;
+    Ok(Json(res))
+}
+
+pub async fn find(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    Path(id): Path<String>,
+) -> Result<Json<Option<PageDto>>, ApiError> {
+    let res = usecases.page().find(&id, true).await?;
+    Ok(Json(res))
+}
+
+pub async fn edit(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    Json(dto): Json<PageDto>,
+) -> Result<Json<PageDto>, ApiError> {
+    let res = usecases.page().edit(&dto).await?;
+    Ok(Json(res))
+}
+
+pub async fn remove(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Extension(_member): Extension<AuthMember>,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let res = usecases.page().remove(&id).await?;
+    Ok(Json(res))
+}
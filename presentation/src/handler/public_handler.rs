@@ -3,19 +3,28 @@ use application::{
     UseCaseModule,
     model::{
         content::ContentDto,
-        search_engine::{SearchQueryDto, SearchRequestDto, SearchResponseDto},
+        page::{PageDto, PageSummaryDto},
+        search_engine::{
+            ArchiveCountDto, RelatedQueryDto, SearchContentDto, SearchQueryDto, SearchRequestDto,
+            SearchResponseDto, SuggestQueryDto,
+        },
     },
 };
 use axum::Json;
 use axum::extract::{Path, Query, State};
+use chrono::Utc;
 use std::sync::Arc;
 
 pub async fn search(
     State(usecases): State<Arc<dyn UseCaseModule>>,
     Json(dto): Json<SearchRequestDto>,
 ) -> Result<Json<SearchResponseDto>, ApiError> {
+    // 下書きと公開日時前の予約投稿は対象外
+    let now = Utc::now();
     let mut pub_dto = dto.clone();
     pub_dto.draft = Some(false);
+    pub_dto.date_from = pub_dto.date_from.map(|from| from.min(now));
+    pub_dto.date_to = Some(pub_dto.date_to.map_or(now, |to| to.min(now)));
     let res = usecases.content().search(&pub_dto).await?;
     Ok(Json(res))
 }
@@ -28,11 +37,13 @@ pub async fn search_query(
         word: None,
         draft: Some(false),
         date_from: None,
-        date_to: None,
+        date_to: Some(Utc::now()),
         tags: None,
         categories: None,
         page: dto.page,
         per_page: dto.per_page,
+        sort: dto.sort,
+        advanced: None,
     };
     let res = usecases.content().search(&pub_dto).await?;
     Ok(Json(res))
@@ -43,25 +54,98 @@ pub async fn find(
     Path(id): Path<String>,
 ) -> Result<Json<Option<ContentDto>>, ApiError> {
     let res = usecases.content().find(&id).await?;
-    let visible = res
-        .as_ref()
-        .filter(|content| matches!(content.matter.as_ref().and_then(|m| m.draft), Some(false)));
+    Ok(Json(res.filter(is_public)))
+}
 
-    Ok(Json(visible.cloned()))
+pub async fn find_by_slug(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Path(slug): Path<String>,
+) -> Result<Json<Option<ContentDto>>, ApiError> {
+    let res = usecases.content().find_by_slug(&slug).await?;
+    Ok(Json(res.filter(is_public)))
 }
 
+// 非公開の記事の関連記事は返さない
+pub async fn related(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Path(id): Path<String>,
+    Query(dto): Query<RelatedQueryDto>,
+) -> Result<Json<Vec<SearchContentDto>>, ApiError> {
+    let content = usecases.content().find(&id).await?;
+    if !content.as_ref().is_some_and(is_public) {
+        return Ok(Json(Vec::new()));
+    }
+    let res = usecases.content().related(&id, dto.limit).await?;
+    Ok(Json(res))
+}
+
+// 公開中の記事のみ数える
 pub async fn tags(
     State(usecases): State<Arc<dyn UseCaseModule>>,
     Path(limit): Path<usize>,
 ) -> Result<Json<Vec<(String, u64)>>, ApiError> {
-    let res = usecases.content().tags(limit).await?;
+    let res = usecases
+        .content()
+        .tags(limit, Some(false), Some(Utc::now()))
+        .await?;
     Ok(Json(res))
 }
 
+// 公開中の記事のみ数える
 pub async fn caregories(
     State(usecases): State<Arc<dyn UseCaseModule>>,
     Path(limit): Path<usize>,
 ) -> Result<Json<Vec<(String, u64)>>, ApiError> {
-    let res = usecases.content().caregories(limit).await?;
+    let res = usecases
+        .content()
+        .caregories(limit, Some(false), Some(Utc::now()))
+        .await?;
+    Ok(Json(res))
+}
+
+pub async fn archives(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+) -> Result<Json<Vec<ArchiveCountDto>>, ApiError> {
+    let res = usecases
+        .content()
+        .archives(Some(false), Some(Utc::now()))
+        .await?;
+    Ok(Json(res))
+}
+
+// 公開中の記事の語句のみ
+pub async fn suggest(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Query(dto): Query<SuggestQueryDto>,
+) -> Result<Json<Vec<(String, u64)>>, ApiError> {
+    let res = usecases
+        .content()
+        .suggest(&dto, Some(false), Some(Utc::now()))
+        .await?;
+    Ok(Json(res))
+}
+
+// 固定ページは下書きを除く
+pub async fn pages(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+) -> Result<Json<Vec<PageSummaryDto>>, ApiError> {
+    let res = usecases.page().list(false).await?;
     Ok(Json(res))
 }
+
+pub async fn page(
+    State(usecases): State<Arc<dyn UseCaseModule>>,
+    Path(id): Path<String>,
+) -> Result<Json<Option<PageDto>>, ApiError> {
+    let res = usecases.page().find(&id, false).await?;
+    Ok(Json(res))
+}
+
+// 下書きと公開日時前の予約投稿は公開しない
+fn is_public(content: &ContentDto) -> bool {
+    let now = Utc::now();
+    content
+        .matter
+        .as_ref()
+        .is_some_and(|m| m.draft == Some(false) && m.date.is_some_and(|date| date <= now))
+}
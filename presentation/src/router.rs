@@ -1,5 +1,5 @@
 use crate::{
-    handler::{auth, content, public_handler},
+    handler::{auth, content, media, page, public_handler},
     middleware::auth::auth_guard,
 };
 use application::UseCaseModule;
@@ -21,10 +21,47 @@ pub fn create_router(usecases: Arc<dyn UseCaseModule>) -> Router {
         .route("/content/search", post(content::search))
         .route("/content/search", get(content::search_query))
         .route("/content/{id}", get(content::find))
+        .route("/content/slug/{slug}", get(content::find_by_slug))
         .route("/content/{id}", delete(content::remove))
         .route("/content", put(content::edit))
+        .route("/content/bulk", post(content::bulk))
         .route("/content/tags/{limit}", get(content::tags))
+        .route("/content/tags/merge", post(content::merge_tags))
         .route("/content/categories/{limit}", get(content::caregories))
+        .route("/content/categories/merge", post(content::merge_categories))
+        .route("/content/archives", get(content::archives))
+        .route("/content/suggest", get(content::suggest))
+        .route("/content/scheduled", get(content::scheduled))
+        .route("/content/check", get(content::check))
+        .route("/content/check/repair", post(content::repair))
+        .route("/content/{id}/revisions", get(content::revisions))
+        .route("/content/{id}/revisions/diff", get(content::diff_revisions))
+        .route("/content/{id}/revisions/{revision}", get(content::revision))
+        .route(
+            "/content/{id}/revisions/{revision}/restore",
+            post(content::restore),
+        )
+        .layer(from_fn_with_state(usecases.clone(), auth_guard));
+
+    let page_router = Router::new()
+        .route("/page", post(page::create))
+        .route("/page", put(page::edit))
+        .route("/page", get(page::list))
+        .route("/page/{id}", get(page::find))
+        .route("/page/{id}", delete(page::remove))
+        .layer(from_fn_with_state(usecases.clone(), auth_guard));
+
+    // アップロードは設定されたサイズ上限 + multipart のオーバーヘッド分まで受け付ける
+    let media_body_limit = CONFIG
+        .media
+        .image_max_size
+        .max(CONFIG.media.attachment_max_size)
+        + 64 * 1024;
+    let media_router = Router::new()
+        .route("/media", post(media::upload))
+        .route("/media", get(media::list))
+        .route("/media/{*name}", delete(media::remove))
+        .layer(DefaultBodyLimit::max(media_body_limit))
         .layer(from_fn_with_state(usecases.clone(), auth_guard));
 
     let mut auth_router = Router::new()
@@ -36,16 +73,24 @@ pub fn create_router(usecases: Arc<dyn UseCaseModule>) -> Router {
 
     let public_router = Router::new()
         .route("/content/{id}", get(public_handler::find))
+        .route("/content/slug/{slug}", get(public_handler::find_by_slug))
+        .route("/content/{id}/related", get(public_handler::related))
         .route("/content/search", post(public_handler::search))
         .route("/content/search", get(public_handler::search_query))
         .route("/content/tags/{limit}", get(public_handler::tags))
         .route(
             "/content/caregories/{limit}",
             get(public_handler::caregories),
-        );
+        )
+        .route("/content/archives", get(public_handler::archives))
+        .route("/content/suggest", get(public_handler::suggest))
+        .route("/page", get(public_handler::pages))
+        .route("/page/{id}", get(public_handler::page));
 
     let manage_router = Router::new()
         .nest("/manage", content_router)
+        .nest("/manage", page_router)
+        .nest("/manage", media_router)
         .nest("/manage", auth_router);
 
     let mut app = Router::new()
@@ -58,8 +103,10 @@ pub fn create_router(usecases: Arc<dyn UseCaseModule>) -> Router {
             .allow_headers([
                 header::CONTENT_TYPE,
                 header::AUTHORIZATION,
+                header::IF_MATCH,
                 "DeviceId".parse().unwrap(),
             ])
+            .expose_headers([header::ETAG])
             .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
             .allow_origin(
                 config::CONFIG
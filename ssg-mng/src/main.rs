@@ -1,9 +1,12 @@
-use std::sync::Arc;
+mod watcher;
+
+use chrono::Utc;
+use std::{sync::Arc, time::Duration};
 use tracing_subscriber::{EnvFilter, fmt};
 
-use application::UseCaseModuleImpl;
+use application::{UseCaseModule, UseCaseModuleImpl};
 use common::types::BoxError;
-use config::CONFIG;
+use config::{CLI, CONFIG, Command};
 use domain::Repositories;
 use infrastructure::RepositoriesImpl;
 use presentation::create_router;
@@ -16,11 +19,21 @@ async fn main() -> Result<(), BoxError> {
         fmt().with_env_filter(filter).init();
     }
 
-    let repos = RepositoriesImpl::new()?;
+    let repos = RepositoriesImpl::new().await?;
     let repos: Arc<dyn Repositories> = Arc::new(repos);
     let usecase = UseCaseModuleImpl::new(repos.clone());
 
-    let app = create_router(Arc::new(usecase));
+    if let Some(command) = CLI.command.as_ref() {
+        return run_command(command, &usecase).await;
+    }
+
+    let usecase: Arc<dyn UseCaseModule> = Arc::new(usecase);
+    spawn_scheduler(usecase.clone());
+    if CONFIG.content.watch {
+        watcher::spawn_watcher(usecase.clone())?;
+    }
+
+    let app = create_router(usecase);
 
     let listener = tokio::net::TcpListener::bind(CONFIG.server.host.clone()).await?;
     tracing::info!("listening on {}", listener.local_addr()?);
@@ -32,6 +45,78 @@ async fn main() -> Result<(), BoxError> {
     Ok(())
 }
 
+async fn run_command(command: &Command, usecase: &UseCaseModuleImpl) -> Result<(), BoxError> {
+    match command {
+        Command::Rebuild => {
+            let report = usecase.content().rebuild().await?;
+            for error in &report.errors {
+                eprintln!("failed: {}", error);
+            }
+            println!(
+                "rebuild finished: processed {}, skipped {}, failed {}",
+                report.processed, report.skipped, report.failed
+            );
+            let pages = usecase.page().rebuild().await?;
+            println!("pages rebuilt: {}", pages);
+        }
+        Command::Check { repair } => {
+            let report = usecase.content().check(*repair).await?;
+            let found = [
+                ("orphan html", &report.orphan_html),
+                ("orphan markdown", &report.orphan_markdown),
+                ("missing markdown", &report.missing_markdown),
+                ("draft html", &report.draft_html),
+            ];
+            for (label, items) in found {
+                for item in items {
+                    println!("{}: {}", label, item);
+                }
+            }
+            for change in &report.changes {
+                println!("repaired: {}", change);
+            }
+            for error in &report.errors {
+                eprintln!("failed: {}", error);
+            }
+            println!(
+                "check finished: orphan html {}, orphan markdown {}, missing markdown {}, draft html {}, repaired {}",
+                report.orphan_html.len(),
+                report.orphan_markdown.len(),
+                report.missing_markdown.len(),
+                report.draft_html.len(),
+                report.changes.len()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// 予約投稿の公開日時を schedule_interval ごとに確認し、迎えたものを公開する
+// 初回は停止中に公開日時を迎えた記事も拾うため全期間を対象にする
+fn spawn_scheduler(usecase: Arc<dyn UseCaseModule>) {
+    tokio::spawn(async move {
+        let period = Duration::from_secs(CONFIG.content.schedule_interval as u64);
+        let mut interval = tokio::time::interval(period);
+        let mut since = None;
+
+        loop {
+            interval.tick().await;
+            let until = Utc::now();
+            match usecase.content().publish_scheduled(since, until).await {
+                Ok(0) => {}
+                Ok(published) => tracing::info!("published {} scheduled contents", published),
+                Err(e) => {
+                    // 次回も同じ期間から再試行する
+                    tracing::warn!("failed to publish scheduled contents: {}", e);
+                    continue;
+                }
+            }
+            since = Some(until);
+        }
+    });
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
@@ -0,0 +1,85 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+use application::UseCaseModule;
+use common::types::BoxError;
+use config::CONFIG;
+
+// ファイルの監視から反映した記事のリビジョンに記録するアカウント
+const WATCH_ACCOUNT: &str = "watcher";
+
+// markdown_dir の *.md の追加・変更・削除を監視し、記事に反映する
+// 最後の変更から watch_debounce_ms の間、変更が無くなってからまとめて反映する
+// サーバー自身の書き込みは sync_markdown 側で最新のリビジョンと比べて無視する
+pub fn spawn_watcher(usecase: Arc<dyn UseCaseModule>) -> Result<(), BoxError> {
+    let dir = PathBuf::from(&CONFIG.content.markdown_dir);
+    std::fs::create_dir_all(&dir)?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+    let mut watcher: RecommendedWatcher =
+        notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                for path in event.paths.into_iter().filter(|p| is_markdown(p)) {
+                    let _ = tx.send(path);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("watch error: {}", e),
+        })?;
+    watcher.watch(&dir, RecursiveMode::Recursive)?;
+    tracing::info!("watching markdown: {}", dir.display());
+
+    tokio::spawn(async move {
+        // 監視を止めないよう保持する
+        let _watcher = watcher;
+        let debounce = Duration::from_millis(CONFIG.content.watch_debounce_ms as u64);
+        let mut pending = BTreeSet::<PathBuf>::new();
+
+        loop {
+            let received = if pending.is_empty() {
+                rx.recv().await
+            } else {
+                match tokio::time::timeout(debounce, rx.recv()).await {
+                    Ok(received) => received,
+                    Err(_) => {
+                        for path in std::mem::take(&mut pending) {
+                            sync(usecase.as_ref(), &path).await;
+                        }
+                        continue;
+                    }
+                }
+            };
+            match received {
+                Some(path) => {
+                    pending.insert(path);
+                }
+                None => break,
+            }
+        }
+    });
+
+    Ok(())
+}
+
+async fn sync(usecase: &dyn UseCaseModule, path: &Path) {
+    let path = path.display().to_string();
+    if let Err(e) = usecase.content().sync_markdown(&path, WATCH_ACCOUNT).await {
+        // 書き込み途中のファイル等は次の変更で再度反映する
+        tracing::warn!("watch failed to sync {}: {}", path, e);
+    }
+}
+
+// 一時ファイル・退避ファイル (. で始まる) は対象外
+fn is_markdown(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
+        && path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| !n.starts_with('.'))
+}